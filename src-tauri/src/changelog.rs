@@ -0,0 +1,269 @@
+use std::fs;
+use futures_util::StreamExt;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::download_source::{build_sources, DownloadTarget};
+use crate::http_client::HttpService;
+use crate::{mod_archive, version_archive};
+use crate::mod_version::cmp_versions;
+
+// Formato do changelog.txt de mods do Factorio:
+// ---------------------------------------------------------------------------------------------------
+// Version: 1.2.0
+// Date: 2024-10-21
+//   Features:
+//     - Entrada
+//       continuação da entrada
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogCategory {
+	pub name: String,
+	pub entries: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogVersion {
+	pub version: String,
+	pub date: Option<String>,
+	pub categories: Vec<ChangelogCategory>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogDiff {
+	pub mod_name: String,
+	pub installed_version: String,
+	pub target_version: String,
+	// Versões depois da instalada até a alvo (ou, num rollback, as que serão desfeitas), mais nova primeiro
+	pub versions: Vec<ChangelogVersion>,
+	// De onde o changelog foi lido: "installed", "archive", "cache" ou a fonte de download
+	pub source: String,
+}
+
+fn indent_of(line: &str) -> usize {
+	line.chars().take_while(|c| *c == ' ' || *c == '\t').map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+// Tolerante com o que o jogo também aceita na prática: espaços sobrando, tabs, CRLF, BOM.
+// Blocos sem "Version:" (cabeçalhos soltos) são ignorados
+pub fn parse(text: &str) -> Vec<ChangelogVersion> {
+	let mut versions = Vec::new();
+	let mut current: Option<ChangelogVersion> = None;
+	// Categoria que recebe as próximas entradas; repetida no mesmo bloco volta para a primeira
+	let mut active: Option<usize> = None;
+	for line in text.trim_start_matches('\u{feff}').lines() {
+		let line = line.trim_end();
+		let trimmed = line.trim_start();
+		if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-') {
+			versions.extend(current.take().filter(|v| !v.version.is_empty()));
+			current = Some(ChangelogVersion { version: String::new(), date: None, categories: Vec::new() });
+			active = None;
+			continue;
+		}
+		let Some(block) = current.as_mut() else { continue; };
+		if trimmed.is_empty() {
+			continue;
+		}
+		if let Some(version) = trimmed.strip_prefix("Version:") {
+			block.version = version.trim().to_string();
+		} else if let Some(date) = trimmed.strip_prefix("Date:") {
+			block.date = Some(date.trim().to_string()).filter(|d| !d.is_empty());
+		} else if let Some(entry) = trimmed.strip_prefix('-') {
+			let index = *active.get_or_insert_with(|| {
+				block.categories.push(ChangelogCategory { name: "Other".into(), entries: Vec::new() });
+				block.categories.len() - 1
+			});
+			block.categories[index].entries.push(entry.trim().to_string());
+		} else if let Some(name) = trimmed.strip_suffix(':').filter(|_| indent_of(line) < 4) {
+			let name = name.trim().to_string();
+			active = Some(match block.categories.iter().position(|c| c.name == name) {
+				Some(index) => index,
+				None => {
+					block.categories.push(ChangelogCategory { name, entries: Vec::new() });
+					block.categories.len() - 1
+				}
+			});
+		} else if let Some(last) = active.and_then(|i| block.categories[i].entries.last_mut()) {
+			// Linha de continuação da entrada anterior
+			last.push('\n');
+			last.push_str(trimmed);
+		}
+	}
+	versions.extend(current.filter(|v| !v.version.is_empty()));
+	versions
+}
+
+// Versões em (from, to], mais nova primeiro
+pub fn between(versions: Vec<ChangelogVersion>, from: &str, to: &str) -> Vec<ChangelogVersion> {
+	use std::cmp::Ordering::*;
+	let mut selected: Vec<ChangelogVersion> = versions
+		.into_iter()
+		.filter(|v| cmp_versions(&v.version, from) == Greater && cmp_versions(&v.version, to) != Greater)
+		.collect();
+	selected.sort_by(|a, b| cmp_versions(&b.version, &a.version));
+	selected
+}
+
+fn read_changelog(path: &Path) -> Result<Option<String>, String> {
+	Ok(mod_archive::read_mod_files(path, |p| p == "changelog.txt")?.into_values().next())
+}
+
+// changelog.txt de versões baixadas só para consulta; zips publicados não mudam sem trocar de versão
+fn cache_path(mod_name: &str, version: &str) -> PathBuf {
+	dirs::data_dir()
+		.unwrap_or(std::env::temp_dir())
+		.join("ModManager")
+		.join("changelogs")
+		.join(format!("{}_{}.txt", mod_name, version))
+}
+
+// Marca, ao lado do cache, uma versão publicada sem changelog.txt: evita baixar o zip de novo a cada consulta
+fn missing_marker(cached: &Path) -> PathBuf {
+	cached.with_extension("none")
+}
+
+// Changelog de <mod>_<versão> sem instalar: pasta de mods e arquivo do perfil, cache,
+// e por fim as fontes de download configuradas (o store compartilhado primeiro)
+async fn changelog_for_version(
+	http: &HttpService,
+	profile_dir: &Path,
+	mods_dir: &Path,
+	mod_name: &str,
+	version: &str,
+) -> Result<(Option<String>, String), String> {
+	let file_name = format!("{}_{}.zip", mod_name, version);
+	for (path, label) in [
+		(mods_dir.join(&file_name), "installed"),
+		(version_archive::archive_dir(profile_dir).join(&file_name), "archive"),
+	] {
+		if path.is_file() {
+			return Ok((read_changelog(&path)?, label.to_string()));
+		}
+	}
+	let cached = cache_path(mod_name, version);
+	if let Ok(text) = fs::read_to_string(&cached) {
+		return Ok((Some(text), "cache".into()));
+	}
+	if missing_marker(&cached).is_file() {
+		return Ok((None, "cache".into()));
+	}
+
+	let app_cfg = crate::load_config().await?;
+	let mut errors = Vec::new();
+	for source in build_sources(&app_cfg.download_sources) {
+		let (path, temporary) = match source.locate(http, mod_name, version).await {
+			Ok(DownloadTarget::Local(path)) | Ok(DownloadTarget::Stored(path)) => (path, false),
+			Ok(DownloadTarget::Http(url)) => {
+				// Nome único: duas consultas da mesma versão ao mesmo tempo não escrevem no mesmo arquivo
+				let tmp = cached.with_file_name(format!("{}_{}.{}.zip.part", mod_name, version, uuid::Uuid::new_v4()));
+				match download_to(http, &url, &tmp).await {
+					Ok(()) => (tmp, true),
+					Err(e) => {
+						errors.push(format!("{}: {}", source.id(), e));
+						continue;
+					}
+				}
+			}
+			Err(e) => {
+				errors.push(format!("{}: {}", source.id(), e.message));
+				continue;
+			}
+		};
+		// Espelho quebrado pode entregar outro mod: confere antes de confiar no changelog
+		let result = match mod_archive::validate_mod_zip_async(&path, mod_name, version).await {
+			Ok(_) => read_changelog(&path),
+			Err(e) => Err(e.message),
+		};
+		if temporary {
+			let _ = fs::remove_file(&path);
+		}
+		match result {
+			Ok(text) => {
+				let cache_result = match text.as_deref() {
+					Some(text) => write_cache(&cached, text),
+					None => write_cache(&missing_marker(&cached), ""),
+				};
+				if let Err(e) = cache_result {
+					println!("Aviso: changelog de {} v{} não foi para o cache: {}", mod_name, version, e);
+				}
+				return Ok((text, source.id()));
+			}
+			Err(e) => errors.push(format!("{}: {}", source.id(), e)),
+		}
+	}
+	Err(format!("Não foi possível obter {} v{}: {}", mod_name, version, errors.join("; ")))
+}
+
+fn write_cache(path: &Path, text: &str) -> std::io::Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	fs::write(path, text)
+}
+
+// Grava o zip direto no disco, bloco a bloco: mods grandes não passam inteiros pela memória
+async fn download_to(http: &HttpService, url: &str, dest: &Path) -> Result<(), String> {
+	use tokio::io::AsyncWriteExt;
+	let response = http.send(http.client().get(url)).await.map_err(|e| format!("Erro na requisição: {}", e))?;
+	if !response.status().is_success() {
+		return Err(format!("Erro HTTP: {}", response.status()));
+	}
+	if let Some(dir) = dest.parent() {
+		fs::create_dir_all(dir).map_err(|e| format!("Erro ao criar {}: {}", dir.display(), e))?;
+	}
+	let file = tokio::fs::File::create(dest).await.map_err(|e| format!("Erro ao criar {}: {}", dest.display(), e))?;
+	let mut file = tokio::io::BufWriter::new(file);
+	let mut stream = response.bytes_stream();
+	let result = async {
+		while let Some(chunk) = stream.next().await {
+			let chunk = chunk.map_err(|e| format!("Erro ao baixar: {}", e.without_url()))?;
+			file.write_all(chunk.as_ref()).await.map_err(|e| format!("Erro ao salvar {}: {}", dest.display(), e))?;
+		}
+		file.flush().await.map_err(|e| format!("Erro ao salvar {}: {}", dest.display(), e))
+	}.await;
+	if result.is_err() {
+		drop(file);
+		let _ = tokio::fs::remove_file(dest).await;
+	}
+	result
+}
+
+// O que muda ao trocar a versão instalada de um mod pela target_version.
+// Atualização: changelog da versão alvo; rollback: da instalada, que já lista as versões desfeitas
+#[tauri::command]
+pub async fn get_mod_changelog(
+	http: tauri::State<'_, HttpService>,
+	profile_name: String,
+	mod_name: String,
+	target_version: String,
+) -> Result<ChangelogDiff, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	let mods_dir = crate::resolve_profile_mods_dir(&profile_dir)?;
+	let installed = crate::load_internal_mod_list(&profile_dir)?
+		.mods
+		.into_iter()
+		.find(|m| m.name == mod_name)
+		.ok_or_else(|| format!("Mod {} não está instalado no perfil", mod_name))?;
+
+	let upgrading = cmp_versions(&target_version, &installed.version) == std::cmp::Ordering::Greater;
+	let (text, source) = if upgrading {
+		changelog_for_version(&http, &profile_dir, &mods_dir, &mod_name, &target_version).await?
+	} else {
+		// Zip, pasta ou symlink: o changelog.txt da cópia instalada
+		let path = mods_dir.join(&installed.file_name);
+		(read_changelog(&path)?, "installed".to_string())
+	};
+
+	let versions = parse(&text.unwrap_or_default());
+	let versions = if upgrading {
+		between(versions, &installed.version, &target_version)
+	} else {
+		between(versions, &target_version, &installed.version)
+	};
+	Ok(ChangelogDiff {
+		mod_name,
+		installed_version: installed.version,
+		target_version,
+		versions,
+		source,
+	})
+}
@@ -2,8 +2,10 @@ use std::collections::{HashMap, VecDeque, HashSet};
 use tauri::{AppHandle, State, Emitter};
 use serde::{Deserialize, Serialize};
 
-use crate::{fetch_mod_full, ModFullData};
+use crate::{fetch_mod_full_internal, ModFullData};
 use crate::download_queue::DownloadQueueManager;
+use crate::{disk_space, download_batch, version_pin};
+use crate::mod_version::cmp_versions;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolveRequest {
@@ -35,19 +37,6 @@ pub async fn resolve_and_enqueue_dependencies(
 	// Track the chosen concrete version per mod (built during traversal)
 	let mut chosen: HashMap<String, String> = HashMap::new();
 
-	// Helper: compare dotted semantic versions
-	fn cmp_versions(a: &str, b: &str) -> std::cmp::Ordering {
-		let pa: Vec<i64> = a.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
-		let pb: Vec<i64> = b.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
-		let max_len = pa.len().max(pb.len());
-		for i in 0..max_len {
-			let ai = *pa.get(i).unwrap_or(&0);
-			let bi = *pb.get(i).unwrap_or(&0);
-			match ai.cmp(&bi) { std::cmp::Ordering::Equal => continue, ord => return ord }
-		}
-		std::cmp::Ordering::Equal
-	}
-
 	// Record a requirement for name to be at least ver
 	fn record_req(required: &mut HashMap<String, String>, name: &str, ver: &str, cmp: fn(&str,&str)->std::cmp::Ordering) {
 		let key = name.to_string();
@@ -60,6 +49,15 @@ pub async fn resolve_and_enqueue_dependencies(
 		}
 	}
 
+	// Pins/restrições dos mods já instalados no perfil
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	let holds = version_pin::load_holds(&profile_dir);
+	let mut conflicts: Vec<version_pin::PinConflict> = Vec::new();
+	if let Some(hold) = holds.get(&root_mod).filter(|h| !h.allows(&version)) {
+		conflicts.push(hold.conflict(&root_mod, &version));
+		return Err(emit_pin_conflicts(&app, &root_mod, &version, &conflicts));
+	}
+
 	// Resolve dependencies breadth-first; carry parent mod and its Factorio version for selection
 	// Queue tuple: (mod_name, requested_or_min_version, parent_mod_name, parent_factorio_version)
 	let mut queue: VecDeque<(String, String, Option<String>, Option<String>)> = VecDeque::new();
@@ -76,12 +74,18 @@ pub async fn resolve_and_enqueue_dependencies(
 		if processed.contains(&name) {
 			continue;
 		}
+		// Dependência fixada no perfil: a versão instalada fica (o requisito mínimo é conferido no fim)
+		let hold = holds.get(&name);
+		if name != root_mod && hold.is_some_and(|h| h.pinned) {
+			processed.insert(name.clone());
+			continue;
+		}
 
 		// Fetch full mod metadata with context (cached)
 		let full: ModFullData = if let Some(cached) = full_cache.get(&name) {
 			cached.clone()
 		} else {
-			match fetch_mod_full(name.clone()).await {
+			match fetch_mod_full_internal(&state.http, name.clone()).await {
 				Ok(f) => { full_cache.insert(name.clone(), f.clone()); f },
 				Err(e) => {
 					let parent_mod = parent.clone().or_else(|| parents.get(&name).cloned());
@@ -132,9 +136,16 @@ pub async fn resolve_and_enqueue_dependencies(
 					.releases
 					.iter()
 					.filter(|r| &r.info_json.factorio_version == fv)
+					.filter(|r| hold.map_or(true, |h| h.allows(&r.version)))
 					.max_by(|a, b| cmp_versions(&a.version, &b.version))
 				{
 					Some(r) => r,
+					// Nenhuma release atende à restrição do perfil: conflito, não erro de seleção
+					None if hold.is_some() => {
+						conflicts.extend(hold.map(|h| h.conflict(&name, &format!(">= {}", ver))));
+						processed.insert(name.clone());
+						continue;
+					}
 					None => {
 						let parent_mod = parent.clone().or_else(|| parents.get(&name).cloned());
 						let msg = if let Some(pm) = &parent_mod {
@@ -245,25 +256,80 @@ pub async fn resolve_and_enqueue_dependencies(
 		}));
 	}
 
+	// Mods fixados precisam atender ao maior requisito mínimo encontrado na árvore
+	for (name, hold) in holds.iter().filter(|(n, h)| h.pinned && **n != root_mod) {
+		if let Some(min) = required.get(name) {
+			if cmp_versions(&hold.installed_version, min) == std::cmp::Ordering::Less {
+				conflicts.push(hold.conflict(name, &format!(">= {}", min)));
+			}
+		}
+	}
+	if !conflicts.is_empty() {
+		return Err(emit_pin_conflicts(&app, &root_mod, &version, &conflicts));
+	}
+
 	// Build final plan from the chosen concrete versions discovered during traversal
 	let mut plan: Vec<(String, String)> = Vec::new();
 	for (name, ver) in chosen.iter() {
 		plan.push((name.clone(), ver.clone()));
 	}
 
-	// Enqueue everything (root included). Ensure higher overrides lower in the queue manager.
-	let mgr = state.inner().clone();
+	// Preflight de espaço: o plano inteiro precisa caber no staging e no diretório de mods do perfil
+	// (tamanhos ficam em cache e já entram nos itens do lote ao enfileirar)
+	let mut expected_bytes: u64 = 0;
+	let mut unknown_sizes: usize = 0;
 	for (name, ver) in plan.iter() {
-		mgr.enqueue_item_direct(&app, name.clone(), ver.clone(), profile_name.clone());
+		match state.estimate_size(name, ver).await {
+			Some(size) => expected_bytes = expected_bytes.saturating_add(size),
+			None => unknown_sizes += 1,
+		}
+	}
+	let mods_dir = crate::get_profiles_dir().and_then(|d| crate::resolve_profile_mods_dir(&d.join(&profile_name)))?;
+	let staging_root = download_batch::staging_dir("");
+	let space_check = disk_space::ensure_free_space(&staging_root, expected_bytes)
+		.and_then(|_| disk_space::ensure_free_space(&mods_dir, expected_bytes));
+	if let Err(e) = space_check {
+		let _ = app.emit("dependency-resolver:error", &serde_json::json!({
+			"root_mod": root_mod,
+			"stage": "disk_space",
+			"mod": root_mod,
+			"requirement": version,
+			"parent_mod": serde_json::Value::Null,
+			"expected_bytes": expected_bytes,
+			"message": e.message,
+		}));
+		return Err(e.message);
 	}
 
+	// Enqueue everything (root included) as one batch: the profile only changes once every item succeeds.
+	let mgr = state.inner().clone();
+	let batch_id = mgr.enqueue_batch(&app, &plan, profile_name.clone());
+
 	let total = plan.len();
 	let deps = if total > 0 { total - 1 } else { 0 };
 	let _ = app.emit("dependency-resolver:finished", &serde_json::json!({
 		"root_mod": root_mod,
 		"total": total,
 		"dependencies": deps,
+		"batch_id": batch_id,
+		"total_bytes": expected_bytes,
+		"unknown_sizes": unknown_sizes,
 	}));
 
 	Ok(format!("{} itens adicionados à fila (com dependências)", total))
 }
+
+// Plano que mudaria mods fixados/restritos não é enfileirado; a lista de conflitos vai para a UI
+fn emit_pin_conflicts(app: &AppHandle, root_mod: &str, version: &str, conflicts: &[version_pin::PinConflict]) -> String {
+	let msg = conflicts.iter().map(|c| c.message.clone()).collect::<Vec<_>>().join("; ");
+	let _ = app.emit("dependency-resolver:error", &serde_json::json!({
+		"root_mod": root_mod,
+		"stage": "pin_conflict",
+		"mod": root_mod,
+		"requirement": version,
+		"parent_mod": serde_json::Value::Null,
+		"conflicts": conflicts,
+		"message": msg,
+	}));
+	msg
+}
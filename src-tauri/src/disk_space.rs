@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use crate::download_failure::{DownloadFailure, FailureKind};
+
+// Folga mantida livre além do tamanho esperado (listas JSON, staging, outros programas)
+const RESERVE_BYTES: u64 = 32 * 1024 * 1024;
+
+// Espaço livre no sistema de arquivos que contém dir (sobe até um ancestral existente)
+pub fn available_space(dir: &Path) -> Option<u64> {
+	let existing = dir.ancestors().find(|p| p.exists())?;
+	fs2::available_space(existing).ok()
+}
+
+// Falha cedo se não couber needed_bytes em dir; espaço desconhecido não bloqueia o download
+pub fn ensure_free_space(dir: &Path, needed_bytes: u64) -> Result<(), DownloadFailure> {
+	let Some(free) = available_space(dir) else { return Ok(()); };
+	let required = needed_bytes.saturating_add(RESERVE_BYTES);
+	if free < required {
+		return Err(DownloadFailure::new(
+			FailureKind::InsufficientSpace,
+			format!(
+				"Espaço insuficiente em {}: necessário {}, disponível {}",
+				dir.display(),
+				format_bytes(required),
+				format_bytes(free)
+			),
+		));
+	}
+	Ok(())
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+	let mb = bytes as f64 / (1024.0 * 1024.0);
+	if mb < 1.0 {
+		format!("{:.1} KB", bytes as f64 / 1024.0)
+	} else if mb < 1024.0 {
+		format!("{:.1} MB", mb)
+	} else {
+		format!("{:.2} GB", mb / 1024.0)
+	}
+}
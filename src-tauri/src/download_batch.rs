@@ -0,0 +1,219 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::download_failure::{DownloadFailure, FailureKind};
+use crate::mod_archive::{self, ArchiveInfoJson};
+use crate::mod_store;
+use crate::hooks::{self, HookContext, HookEvent};
+use crate::download_queue::{DownloadItem, DownloadQueueManager, DownloadStatus};
+use crate::version_archive;
+
+// Itens de um lote (ex.: plano do resolvedor) são baixados para uma área de staging
+// e só entram no perfil quando todos terminam com sucesso.
+pub fn staging_dir(batch_id: &str) -> PathBuf {
+	dirs::data_dir()
+		.unwrap_or(std::env::temp_dir())
+		.join("ModManager")
+		.join("staging")
+		.join(batch_id)
+}
+
+pub fn discard_staging(batch_id: &str) {
+	let _ = fs::remove_dir_all(staging_dir(batch_id));
+}
+
+fn is_pending(item: &DownloadItem) -> bool {
+	match &item.status {
+		DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::Paused => true,
+		// Falha transitória com retry agendado ainda pode concluir
+		DownloadStatus::Failed(_) => item.next_retry_at.is_some(),
+		_ => false,
+	}
+}
+
+// Lote pronto para commit: nada pendente, nenhuma falha e ao menos um item em staging
+fn batch_ready(items: &[DownloadItem]) -> bool {
+	!items.is_empty()
+		&& !items.iter().any(is_pending)
+		&& !items.iter().any(|i| matches!(i.status, DownloadStatus::Failed(_)))
+		&& items.iter().any(|i| i.staged)
+}
+
+// Chamado após cada item de lote terminar: aborta o lote em falha definitiva ou faz o commit quando completo
+pub async fn on_batch_item_finished(manager: &DownloadQueueManager, app: &AppHandle, batch_id: &str) {
+	let items: Vec<DownloadItem> = manager.queue.lock().unwrap()
+		.iter()
+		.filter(|i| i.batch_id.as_deref() == Some(batch_id))
+		.cloned()
+		.collect();
+
+	let failed = items.iter().find(|i| matches!(i.status, DownloadStatus::Failed(_)) && i.next_retry_at.is_none());
+	if let Some(failed) = failed {
+		abort_batch(manager, app, batch_id, &format!("{} v{}", failed.mod_name, failed.version));
+		return;
+	}
+	if !batch_ready(&items) { return; }
+
+	match commit_batch(&items).await {
+		Ok(installed) => {
+			let mut q = manager.queue.lock().unwrap();
+			for entry in q.iter_mut().filter(|i| i.batch_id.as_deref() == Some(batch_id)) {
+				entry.staged = false;
+			}
+			drop(q);
+			discard_staging(batch_id);
+			let _ = app.emit("download-queue:batch-committed", &serde_json::json!({
+				"batch_id": batch_id,
+				"count": items.iter().filter(|i| i.staged).count(),
+			}));
+			for (previous_version, context) in installed {
+				hooks::fire_installed(app, previous_version, context);
+			}
+			fire_batch_finished(app, batch_id, &items, "committed");
+		}
+		Err(e) => {
+			let mut q = manager.queue.lock().unwrap();
+			for entry in q.iter_mut().filter(|i| i.batch_id.as_deref() == Some(batch_id)) {
+				entry.staged = false;
+				entry.status = DownloadStatus::Failed(e.clone());
+			}
+			drop(q);
+			discard_staging(batch_id);
+			let _ = app.emit("download-queue:batch-failed", &serde_json::json!({
+				"batch_id": batch_id,
+				"message": e.message,
+			}));
+			fire_batch_finished(app, batch_id, &items, "failed");
+		}
+	}
+	manager.save_persist();
+	manager.emit_update(app);
+	manager.archive_finished(app);
+}
+
+fn fire_batch_finished(app: &AppHandle, batch_id: &str, items: &[DownloadItem], status: &str) {
+	hooks::fire(app, HookEvent::BatchFinished, HookContext {
+		profile_name: items.first().map(|i| i.profile_name.clone()),
+		batch_id: Some(batch_id.to_string()),
+		batch_status: Some(status.to_string()),
+		..Default::default()
+	});
+}
+
+// Falha definitiva em um item: os demais itens do lote não são aplicados ao perfil
+pub fn abort_batch(manager: &DownloadQueueManager, app: &AppHandle, batch_id: &str, culprit: &str) {
+	let reason = format!("Lote cancelado: falha em {}", culprit);
+	let mut q = manager.queue.lock().unwrap();
+	let items: Vec<DownloadItem> = q.iter().filter(|i| i.batch_id.as_deref() == Some(batch_id)).cloned().collect();
+	for entry in q.iter_mut().filter(|i| i.batch_id.as_deref() == Some(batch_id)) {
+		entry.staged = false;
+		if !matches!(entry.status, DownloadStatus::Failed(_)) || entry.next_retry_at.is_some() {
+			entry.status = DownloadStatus::Failed(DownloadFailure::other(reason.clone()));
+			entry.next_retry_at = None;
+		}
+	}
+	drop(q);
+	discard_staging(batch_id);
+	manager.save_persist();
+	manager.emit_update(app);
+	let _ = app.emit("download-queue:batch-failed", &serde_json::json!({
+		"batch_id": batch_id,
+		"message": reason,
+	}));
+	fire_batch_finished(app, batch_id, &items, "failed");
+	manager.archive_finished(app);
+}
+
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+	// rename falha entre volumes diferentes (staging em outro disco que mods_path customizado)
+	if fs::rename(from, to).is_err() {
+		// to pode ser hardlink do store: desvincular em vez de truncar o objeto compartilhado
+		let _ = fs::remove_file(to);
+		fs::copy(from, to)?;
+		fs::remove_file(from)?;
+	}
+	Ok(())
+}
+
+// Aplica o lote ao perfil; se qualquer passo falhar, restaura mod-list.json/internal-mod-list.json, remove os zips
+// colocados e devolve os que foram sobrescritos.
+// Retorna, por mod aplicado, a versão substituída e o contexto para os hooks de instalação
+async fn commit_batch(items: &[DownloadItem]) -> Result<Vec<(Option<String>, HookContext)>, DownloadFailure> {
+	let staged: Vec<&DownloadItem> = items.iter().filter(|i| i.staged).collect();
+	let Some(first) = staged.first() else { return Ok(Vec::new()); };
+	let profile_dir = super::get_profiles_dir().map_err(DownloadFailure::other)?.join(&first.profile_name);
+	let mods_dir = super::resolve_profile_mods_dir(&profile_dir).map_err(DownloadFailure::disk)?;
+	let batch_dir = staging_dir(first.batch_id.as_deref().unwrap_or_default());
+
+	// Metadados lidos do info.json de cada zip do staging antes de tocar no perfil
+	let mut planned: Vec<(&DownloadItem, String, ArchiveInfoJson)> = Vec::new();
+	for item in staged.iter() {
+		let file_name = format!("{}_{}.zip", item.mod_name, item.version);
+		let info = mod_archive::read_info_json_async(&batch_dir.join(&file_name)).await
+			.map_err(|e| DownloadFailure::new(FailureKind::Integrity, e))?;
+		planned.push((item, file_name, info));
+	}
+
+	let list_paths = [mods_dir.join("mod-list.json"), mods_dir.join("internal-mod-list.json")];
+	let backups: Vec<Option<Vec<u8>>> = list_paths.iter().map(|p| fs::read(p).ok()).collect();
+	let mut placed: Vec<PathBuf> = Vec::new();
+	// Zips que já existiam no destino: (cópia de segurança no staging, destino)
+	let backup_dir = batch_dir.join("backup");
+	let mut overwritten: Vec<(PathBuf, PathBuf)> = Vec::new();
+	let mut installed: Vec<(Option<String>, HookContext)> = Vec::new();
+
+	let result = (|| -> Result<(), DownloadFailure> {
+		for (item, file_name, info) in planned.iter() {
+			let dest = mods_dir.join(file_name);
+			if dest.exists() {
+				fs::create_dir_all(&backup_dir).map_err(DownloadFailure::disk)?;
+				let backup = backup_dir.join(file_name);
+				move_file(&dest, &backup).map_err(DownloadFailure::disk)?;
+				overwritten.push((backup, dest.clone()));
+			}
+			move_file(&batch_dir.join(file_name), &dest).map_err(DownloadFailure::disk)?;
+			if !overwritten.iter().any(|(_, d)| *d == dest) { placed.push(dest.clone()); }
+			let previous_version = super::add_mod_to_lists(&profile_dir, file_name, info)
+				.map_err(DownloadFailure::disk)?;
+			installed.push((previous_version, HookContext {
+				mod_name: Some(item.mod_name.clone()),
+				version: Some(item.version.clone()),
+				profile_name: Some(item.profile_name.clone()),
+				file_path: Some(dest.to_string_lossy().to_string()),
+				mods_dir: Some(mods_dir.to_string_lossy().to_string()),
+				batch_id: item.batch_id.clone(),
+				..Default::default()
+			}));
+		}
+		Ok(())
+	})();
+
+	if let Err(e) = result {
+		for path in placed.iter() { let _ = fs::remove_file(path); }
+		for (backup, dest) in overwritten.iter() {
+			if let Err(err) = move_file(backup, dest) {
+				println!("Aviso: não foi possível restaurar {}: {}", dest.display(), err);
+			}
+		}
+		for (path, backup) in list_paths.iter().zip(backups) {
+			match backup {
+				Some(bytes) => { let _ = fs::write(path, bytes); }
+				None => { let _ = fs::remove_file(path); }
+			}
+		}
+		return Err(DownloadFailure { message: format!("Falha ao aplicar lote (perfil restaurado): {}", e.message), ..e });
+	}
+	let _ = fs::remove_dir_all(&backup_dir);
+
+	// Somente após o commit as versões antigas saem do perfil (para o arquivo, permitindo rollback)
+	let retention = version_archive::archive_retention().await;
+	for (item, file_name, _) in planned.iter() {
+		version_archive::archive_other_versions(&profile_dir, &mods_dir, &item.mod_name, file_name, retention);
+		let sha1 = item.outcome.as_ref().and_then(|o| o.sha1.as_deref());
+		if let Err(e) = mod_store::adopt(&mods_dir.join(file_name), &item.mod_name, &item.version, sha1) {
+			println!("Aviso: {} v{} não entrou no store: {}", item.mod_name, item.version, e);
+		}
+	}
+	Ok(installed)
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Categoria da falha; decide se a fila tenta novamente sozinha
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+	NotFound,    // HTTP 404/410
+	ServerError, // HTTP 5xx
+	RateLimited, // HTTP 429
+	HttpClient,  // demais 4xx
+	Network,     // conexão, DNS, timeout, stream interrompido
+	Disk,        // erro de escrita/leitura local
+	InsufficientSpace, // sem espaço livre para o arquivo esperado
+	Integrity,   // arquivo incompleto ou inválido
+	Cancelled,   // cancelado/pausado pelo usuário
+	Deferred,    // interrompido porque a janela de horário encerrou
+	Other,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DownloadFailure {
+	pub kind: FailureKind,
+	pub message: String,
+	#[serde(default)]
+	pub http_status: Option<u16>,
+}
+
+// Aceita tanto o formato estruturado quanto o antigo Failed("mensagem") da fila persistida
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FailureRepr {
+	Legacy(String),
+	Structured {
+		kind: FailureKind,
+		message: String,
+		#[serde(default)]
+		http_status: Option<u16>,
+	},
+}
+
+impl<'de> Deserialize<'de> for DownloadFailure {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Ok(match FailureRepr::deserialize(deserializer)? {
+			FailureRepr::Legacy(message) => DownloadFailure { kind: FailureKind::Other, message, http_status: None },
+			FailureRepr::Structured { kind, message, http_status } => DownloadFailure { kind, message, http_status },
+		})
+	}
+}
+
+impl DownloadFailure {
+	pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+		Self { kind, message: message.into(), http_status: None }
+	}
+
+	pub fn http(status: reqwest::StatusCode) -> Self {
+		let kind = match status.as_u16() {
+			404 | 410 => FailureKind::NotFound,
+			429 => FailureKind::RateLimited,
+			500..=599 => FailureKind::ServerError,
+			_ => FailureKind::HttpClient,
+		};
+		Self { kind, message: format!("Erro HTTP: {}", status), http_status: Some(status.as_u16()) }
+	}
+
+	pub fn network(e: impl fmt::Display) -> Self {
+		Self::new(FailureKind::Network, format!("Erro de rede: {}", e))
+	}
+
+	pub fn disk(e: impl fmt::Display) -> Self {
+		Self::new(FailureKind::Disk, format!("Erro de disco: {}", e))
+	}
+
+	pub fn other(e: impl fmt::Display) -> Self {
+		Self::new(FailureKind::Other, e.to_string())
+	}
+
+	pub fn cancelled() -> Self {
+		Self::new(FailureKind::Cancelled, "Cancelado")
+	}
+
+	pub fn deferred() -> Self {
+		Self::new(FailureKind::Deferred, "Fora da janela de download")
+	}
+
+	// Falhas transitórias são repetidas com backoff; as demais falham de imediato
+	pub fn is_transient(&self) -> bool {
+		matches!(self.kind, FailureKind::ServerError | FailureKind::RateLimited | FailureKind::Network)
+	}
+
+	// Interrupções não são falhas da fonte: não disparam failover nem retry
+	pub fn is_interruption(&self) -> bool {
+		matches!(self.kind, FailureKind::Cancelled | FailureKind::Deferred)
+	}
+}
+
+impl fmt::Display for DownloadFailure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl From<DownloadFailure> for String {
+	fn from(f: DownloadFailure) -> Self {
+		f.message
+	}
+}
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use crate::download_failure::DownloadFailure;
+use crate::download_queue::{DownloadItem, DownloadQueueManager, DownloadStatus};
+
+// Resultado de um download bem-sucedido, guardado no item até ele ir para o histórico
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DownloadOutcome {
+	pub bytes: u64,
+	pub source: String,
+	pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+	pub id: String,
+	pub mod_name: String,
+	pub version: String,
+	pub profile_name: String,
+	#[serde(default)]
+	pub batch_id: Option<String>,
+	pub succeeded: bool,
+	#[serde(default)]
+	pub failure: Option<DownloadFailure>,
+	pub added_at: String,
+	#[serde(default)]
+	pub started_at: Option<String>,
+	pub finished_at: String,
+	pub bytes: u64,
+	pub avg_speed_bps: f64,
+	#[serde(default)]
+	pub source: Option<String>,
+	#[serde(default)]
+	pub sha1: Option<String>,
+	pub attempts: u32,
+}
+
+impl HistoryEntry {
+	pub fn from_item(item: &DownloadItem) -> Self {
+		// Itens cancelados/removidos sem passar pelo fim da transferência usam o momento atual
+		let finished_at = item.finished_at.as_deref()
+			.and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+			.map(|t| t.with_timezone(&chrono::Utc))
+			.unwrap_or_else(chrono::Utc::now);
+		let bytes = item.outcome.as_ref().map(|o| o.bytes).unwrap_or(0);
+		// Velocidade média sobre o tempo entre início e fim da última tentativa
+		let avg_speed_bps = item.started_at.as_deref()
+			.and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+			.map(|start| (finished_at - start.with_timezone(&chrono::Utc)).num_milliseconds())
+			.filter(|ms| *ms > 0)
+			.map(|ms| bytes as f64 / (ms as f64 / 1000.0))
+			.unwrap_or(0.0);
+		let failure = match &item.status {
+			DownloadStatus::Failed(f) => Some(f.clone()),
+			_ => None,
+		};
+		HistoryEntry {
+			id: item.id.clone(),
+			mod_name: item.mod_name.clone(),
+			version: item.version.clone(),
+			profile_name: item.profile_name.clone(),
+			batch_id: item.batch_id.clone(),
+			succeeded: failure.is_none(),
+			failure,
+			added_at: item.added_at.clone(),
+			started_at: item.started_at.clone(),
+			finished_at: finished_at.to_rfc3339(),
+			bytes,
+			avg_speed_bps,
+			source: item.outcome.as_ref().map(|o| o.source.clone()),
+			sha1: item.outcome.as_ref().and_then(|o| o.sha1.clone()),
+			attempts: item.attempts,
+		}
+	}
+}
+
+// Histórico de downloads finalizados/falhos, separado da fila (download-history.json)
+#[derive(Default, Clone)]
+pub struct DownloadHistory {
+	entries: Arc<Mutex<Option<Vec<HistoryEntry>>>>,
+	// Dias que uma entrada é mantida; 0 = para sempre
+	retention_days: Arc<Mutex<u32>>,
+}
+
+impl DownloadHistory {
+	fn path() -> PathBuf {
+		let data_dir = dirs::data_dir().unwrap_or(std::env::temp_dir()).join("ModManager");
+		let _ = fs::create_dir_all(&data_dir);
+		data_dir.join("download-history.json")
+	}
+
+	// Executa f com as entradas carregadas (lazy) do disco
+	fn with_entries<R>(&self, f: impl FnOnce(&mut Vec<HistoryEntry>) -> R) -> R {
+		let mut guard = self.entries.lock().unwrap();
+		let entries = guard.get_or_insert_with(|| {
+			fs::read_to_string(Self::path())
+				.ok()
+				.and_then(|c| serde_json::from_str::<Vec<HistoryEntry>>(&c).ok())
+				.unwrap_or_default()
+		});
+		f(entries)
+	}
+
+	fn save(entries: &[HistoryEntry]) {
+		if let Ok(json) = serde_json::to_string_pretty(entries) { let _ = fs::write(Self::path(), json); }
+	}
+
+	fn prune(entries: &mut Vec<HistoryEntry>, retention_days: u32) {
+		if retention_days == 0 { return; }
+		let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days as i64);
+		entries.retain(|e| {
+			chrono::DateTime::parse_from_rfc3339(&e.finished_at)
+				.map(|t| t >= cutoff)
+				.unwrap_or(true)
+		});
+	}
+
+	pub fn set_retention_days(&self, days: u32) {
+		*self.retention_days.lock().unwrap() = days;
+		self.with_entries(|entries| {
+			let before = entries.len();
+			Self::prune(entries, days);
+			if entries.len() != before { Self::save(entries); }
+		});
+	}
+
+	pub fn record(&self, new_entries: Vec<HistoryEntry>) {
+		if new_entries.is_empty() { return; }
+		let retention = *self.retention_days.lock().unwrap();
+		self.with_entries(|entries| {
+			entries.extend(new_entries);
+			Self::prune(entries, retention);
+			Self::save(entries);
+		});
+	}
+
+	pub fn query(&self, profile_name: Option<&str>, mod_name: Option<&str>) -> Vec<HistoryEntry> {
+		self.with_entries(|entries| {
+			let mut found: Vec<HistoryEntry> = entries
+				.iter()
+				.filter(|e| profile_name.is_none_or(|p| e.profile_name == p))
+				.filter(|e| mod_name.is_none_or(|m| e.mod_name == m))
+				.cloned()
+				.collect();
+			// Mais recentes primeiro
+			found.sort_by(|a, b| b.finished_at.cmp(&a.finished_at));
+			found
+		})
+	}
+
+	pub fn clear(&self) {
+		self.with_entries(|entries| {
+			entries.clear();
+			Self::save(entries);
+		});
+	}
+}
+
+#[tauri::command]
+pub fn get_download_history(
+	state: State<DownloadQueueManager>,
+	profile_name: Option<String>,
+	mod_name: Option<String>,
+) -> Result<Vec<HistoryEntry>, String> {
+	Ok(state.history.query(profile_name.as_deref(), mod_name.as_deref()))
+}
+
+#[tauri::command]
+pub fn clear_download_history(state: State<DownloadQueueManager>) -> Result<String, String> {
+	state.history.clear();
+	Ok("Histórico limpo".into())
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveTime;
+use tokio::time::{Duration, Instant};
+
+// Janela de horário (hora local) em que a fila pode baixar, ex.: 23:00 -> 07:00
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DownloadWindow {
+	pub start: String, // "HH:MM"
+	pub end: String,   // "HH:MM"; se menor que start, a janela atravessa a meia-noite
+}
+
+impl DownloadWindow {
+	fn contains(&self, now: NaiveTime) -> bool {
+		let (Ok(start), Ok(end)) = (
+			NaiveTime::parse_from_str(&self.start, "%H:%M"),
+			NaiveTime::parse_from_str(&self.end, "%H:%M"),
+		) else {
+			// Janela mal formatada não deve travar a fila
+			return true;
+		};
+		if start <= end {
+			now >= start && now < end
+		} else {
+			now >= start || now < end
+		}
+	}
+}
+
+// Limites aplicados pela fila; atualizados em tempo real quando a configuração é salva
+#[derive(Debug, Clone, Default)]
+pub struct QueueLimits {
+	pub global_limit_bps: Option<u64>,
+	pub windows: Vec<DownloadWindow>,
+}
+
+impl QueueLimits {
+	// Sem janelas configuradas a fila pode rodar a qualquer hora
+	pub fn is_within_schedule(&self) -> bool {
+		if self.windows.is_empty() { return true; }
+		let now = chrono::Local::now().time();
+		self.windows.iter().any(|w| w.contains(now))
+	}
+
+	// Limite efetivo para um item: o menor entre o global e o do próprio item
+	pub fn effective_limit(&self, item_limit_bps: Option<u64>) -> Option<u64> {
+		match (self.global_limit_bps.filter(|l| *l > 0), item_limit_bps.filter(|l| *l > 0)) {
+			(Some(g), Some(i)) => Some(g.min(i)),
+			(g, i) => g.or(i),
+		}
+	}
+}
+
+// Controle de taxa simples: calcula quanto esperar para manter bytes/s abaixo do limite
+pub struct RateLimiter {
+	window_start: Instant,
+	window_bytes: u64,
+	current_limit: Option<u64>,
+}
+
+impl Default for RateLimiter {
+	fn default() -> Self {
+		Self { window_start: Instant::now(), window_bytes: 0, current_limit: None }
+	}
+}
+
+impl RateLimiter {
+	pub fn delay_for(&mut self, bytes: u64, limit_bps: Option<u64>) -> Option<Duration> {
+		// Limite mudou (configuração salva): recomeçar a janela de medição
+		if limit_bps != self.current_limit {
+			self.current_limit = limit_bps;
+			self.window_start = Instant::now();
+			self.window_bytes = 0;
+		}
+		let limit = limit_bps?;
+		self.window_bytes += bytes;
+		let expected = Duration::from_secs_f64(self.window_bytes as f64 / limit as f64);
+		let elapsed = self.window_start.elapsed();
+		if expected > elapsed {
+			return Some(expected - elapsed);
+		}
+		// Evitar acumular "crédito" após períodos lentos
+		if elapsed > Duration::from_secs(2) {
+			self.window_start = Instant::now();
+			self.window_bytes = 0;
+		}
+		None
+	}
+}
@@ -1,14 +1,28 @@
 
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tauri::{State, AppHandle, Emitter};
 use uuid::Uuid;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::time::Instant;
 use tokio::sync::oneshot;
-use reqwest::Client;
 use futures_util::StreamExt;
+use crate::download_source::{build_sources, probe_size, DownloadTarget};
+use crate::download_limits::{QueueLimits, RateLimiter};
+use crate::download_failure::{DownloadFailure, FailureKind};
+use crate::download_batch;
+use crate::download_history::{DownloadHistory, DownloadOutcome, HistoryEntry};
+use crate::http_client::{HostResponse, HttpService, API_TIMEOUT};
+use crate::disk_space;
+use crate::mod_store;
+use crate::mod_archive;
+use crate::mod_install;
+use crate::version_pin;
+use crate::version_archive;
+use crate::hooks;
+use crate::mod_version::cmp_versions;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DownloadStatus {
@@ -16,11 +30,11 @@ pub enum DownloadStatus {
 	Downloading,
 	Paused,
 	Completed,
-	Failed(String),
+	Failed(DownloadFailure),
 	Removed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DownloadItem {
 	pub id: String,
 	pub mod_name: String,
@@ -31,45 +45,315 @@ pub struct DownloadItem {
 	pub added_at: String,
 	pub speed_bps: f64,
 	pub eta_secs: Option<u64>,
+	// Limite de banda específico deste item (bytes/s); o global em AppConfig também se aplica
+	#[serde(default)]
+	pub rate_limit_bps: Option<u64>,
+	// Tentativas automáticas já feitas e quando a próxima está agendada (RFC 3339)
+	#[serde(default)]
+	pub attempts: u32,
+	#[serde(default)]
+	pub next_retry_at: Option<String>,
+	// Lote de origem (ex.: um plano do resolvedor); itens de lote só entram no perfil juntos
+	#[serde(default)]
+	pub batch_id: Option<String>,
+	// Baixado para a área de staging do lote, aguardando commit
+	#[serde(default)]
+	pub staged: bool,
+	#[serde(default)]
+	pub started_at: Option<String>,
+	// Fim da transferência (sucesso ou falha); itens de lote só vão para o histórico depois do commit
+	#[serde(default)]
+	pub finished_at: Option<String>,
+	// Bytes, fonte e checksum do download concluído (vai para o histórico)
+	#[serde(default)]
+	pub outcome: Option<DownloadOutcome>,
+	// Tamanho esperado do arquivo (HEAD/Content-Length); None enquanto desconhecido
+	#[serde(default)]
+	pub expected_bytes: Option<u64>,
+	// Maior prioridade vai para a frente da fila ao ordenar por prioridade
+	#[serde(default)]
+	pub priority: i32,
+}
+
+// Política de retry para falhas transitórias (5xx, 429, rede)
+const MAX_AUTO_RETRIES: u32 = 5;
+const RETRY_BASE_SECS: u64 = 5;
+const RETRY_MAX_SECS: u64 = 600;
+
+fn retry_backoff(attempts: u32) -> std::time::Duration {
+	let secs = RETRY_BASE_SECS.saturating_mul(1u64 << attempts.saturating_sub(1).min(16));
+	std::time::Duration::from_secs(secs.min(RETRY_MAX_SECS))
+}
+
+// Item pronto para iniciar: na fila, ou falha transitória cujo horário de retry já passou
+fn is_ready_to_start(item: &DownloadItem, now: chrono::DateTime<chrono::Utc>) -> bool {
+	match &item.status {
+		DownloadStatus::Queued => true,
+		DownloadStatus::Failed(_) => item.next_retry_at.as_deref()
+			.and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+			.is_some_and(|t| t <= now),
+		_ => false,
+	}
+}
+
+// Falha transitória aguardando nova tentativa: pausar precisa virar Paused, senão o
+// archive_finished a trataria como falha definitiva
+fn is_retry_pending(item: &DownloadItem) -> bool {
+	matches!(item.status, DownloadStatus::Failed(_)) && item.next_retry_at.is_some()
+}
+
+// Evento incremental da fila (download-queue:event); o frontend aplica sobre o último snapshot
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueueEvent {
+	Added { index: usize, item: DownloadItem },
+	Progress { id: String, progress: f32, speed_bps: f64, eta_secs: Option<u64> },
+	StatusChanged { item: DownloadItem },
+	Removed { id: String },
+	// Nova ordem completa dos ids, enviada só quando a ordem muda
+	Reordered { ids: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct QueueEventEnvelope<'a> {
+	seq: u64,
+	#[serde(flatten)]
+	event: &'a QueueEvent,
+}
+
+// Estado da fila como o frontend o conhece, usado para emitir só as diferenças
+#[derive(Default)]
+pub struct QueueEventState {
+	seq: u64,
+	last_emitted: Vec<DownloadItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSnapshot {
+	pub seq: u64,
+	pub items: Vec<DownloadItem>,
+}
+
+// Mudou só o andamento do download (progresso, velocidade, ETA)?
+fn only_progress_changed(old: &DownloadItem, new: &DownloadItem) -> bool {
+	let mut normalized = new.clone();
+	normalized.progress = old.progress;
+	normalized.speed_bps = old.speed_bps;
+	normalized.eta_secs = old.eta_secs;
+	normalized == *old
+}
+
+fn diff_queue(prev: &[DownloadItem], next: &[DownloadItem]) -> Vec<QueueEvent> {
+	let prev_by_id: HashMap<&str, &DownloadItem> = prev.iter().map(|i| (i.id.as_str(), i)).collect();
+	let next_ids: HashSet<&str> = next.iter().map(|i| i.id.as_str()).collect();
+	let mut events: Vec<QueueEvent> = prev.iter()
+		.filter(|i| !next_ids.contains(i.id.as_str()))
+		.map(|i| QueueEvent::Removed { id: i.id.clone() })
+		.collect();
+	for (index, item) in next.iter().enumerate() {
+		match prev_by_id.get(item.id.as_str()) {
+			None => events.push(QueueEvent::Added { index, item: item.clone() }),
+			Some(old) if *old != item => {
+				if only_progress_changed(old, item) {
+					events.push(QueueEvent::Progress { id: item.id.clone(), progress: item.progress, speed_bps: item.speed_bps, eta_secs: item.eta_secs });
+				} else {
+					events.push(QueueEvent::StatusChanged { item: item.clone() });
+				}
+			}
+			_ => {}
+		}
+	}
+	// Itens que já existiam mudaram de posição relativa?
+	let kept_prev: Vec<&str> = prev.iter().map(|i| i.id.as_str()).filter(|id| next_ids.contains(id)).collect();
+	let kept_next: Vec<&str> = next.iter().map(|i| i.id.as_str()).filter(|id| prev_by_id.contains_key(id)).collect();
+	if kept_prev != kept_next {
+		events.push(QueueEvent::Reordered { ids: next.iter().map(|i| i.id.clone()).collect() });
+	}
+	events
+}
+
+// Seleção de itens por perfil e/ou lote de origem; campos ausentes não filtram
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueFilter {
+	#[serde(default)]
+	pub profile_name: Option<String>,
+	#[serde(default)]
+	pub batch_id: Option<String>,
+}
+
+impl QueueFilter {
+	pub fn matches(&self, item: &DownloadItem) -> bool {
+		self.profile_name.as_deref().is_none_or(|p| item.profile_name == p)
+			&& self.batch_id.as_deref().is_none_or(|b| item.batch_id.as_deref() == Some(b))
+	}
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueGrouping {
+	Profile,
+	Batch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueGroup {
+	// profile_name ou batch_id; None = itens avulsos (sem lote)
+	pub key: Option<String>,
+	pub items: Vec<DownloadItem>,
+	pub queued: usize,
+	pub downloading: usize,
+	pub paused: usize,
+	pub failed: usize,
+}
+
+// Estimativa de bytes para a fila inteira (itens ainda por baixar)
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueEstimate {
+	pub total_bytes: u64,
+	pub remaining_bytes: u64,
+	// Itens pendentes cujo tamanho ainda não é conhecido (não entram nos totais)
+	pub unknown_items: usize,
+	pub eta_secs: Option<u64>,
 }
 
 #[derive(Default, Clone)]
 pub struct DownloadQueueManager {
 	pub queue: Arc<Mutex<Vec<DownloadItem>>>,
+	pub events: Arc<Mutex<QueueEventState>>,
 	pub paused_all: Arc<Mutex<bool>>,
 	pub persist_path: Arc<Mutex<Option<PathBuf>>>,
 	pub cancel_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+	pub limits: Arc<Mutex<QueueLimits>>,
+	pub history: DownloadHistory,
+	pub http: HttpService,
+	// Tamanhos já consultados por "<mod>@<versão>", para não repetir HEADs; None = nenhuma fonte informou
+	pub size_cache: Arc<Mutex<HashMap<String, Option<u64>>>>,
 }
 
 impl DownloadQueueManager {
+	fn emit_event(app: &AppHandle, state: &mut QueueEventState, event: &QueueEvent) {
+		state.seq += 1;
+		let _ = app.emit("download-queue:event", &QueueEventEnvelope { seq: state.seq, event });
+	}
+
+	// Emite apenas o que mudou desde o último evento (itens adicionados, alterados, removidos, nova ordem)
 	pub(crate) fn emit_update(&self, app: &AppHandle) {
-		if let Ok(q) = self.queue.lock() {
-			let _ = app.emit("download-queue:update", &*q);
+		let Ok(q) = self.queue.lock() else { return; };
+		let mut state = self.events.lock().unwrap();
+		let events = diff_queue(&state.last_emitted, &q);
+		if events.is_empty() { return; }
+		state.last_emitted = q.clone();
+		for event in events.iter() {
+			Self::emit_event(app, &mut state, event);
 		}
 	}
 
-	// Compare two dotted version strings (e.g., 1.0.2 > 1.0.1)
-	fn cmp_versions(a: &str, b: &str) -> std::cmp::Ordering {
-		let pa: Vec<i64> = a.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
-		let pb: Vec<i64> = b.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
-		let max_len = pa.len().max(pb.len());
-		for i in 0..max_len {
-			let ai = *pa.get(i).unwrap_or(&0);
-			let bi = *pb.get(i).unwrap_or(&0);
-			match ai.cmp(&bi) { std::cmp::Ordering::Equal => continue, ord => return ord }
+	// Caminho rápido para o tick de progresso: um evento para o item, sem comparar a fila inteira
+	fn emit_progress(&self, app: &AppHandle, id: &str) {
+		let Ok(q) = self.queue.lock() else { return; };
+		let Some(item) = q.iter().find(|i| i.id == id) else { return; };
+		let mut state = self.events.lock().unwrap();
+		let Some(known) = state.last_emitted.iter_mut().find(|i| i.id == id) else { return; };
+		known.progress = item.progress;
+		known.speed_bps = item.speed_bps;
+		known.eta_secs = item.eta_secs;
+		let event = QueueEvent::Progress { id: item.id.clone(), progress: item.progress, speed_bps: item.speed_bps, eta_secs: item.eta_secs };
+		Self::emit_event(app, &mut state, &event);
+	}
+
+	// Fila atual e o seq do último evento já refletido nela (para o frontend ressincronizar)
+	pub fn snapshot(&self) -> QueueSnapshot {
+		let q = self.queue.lock().unwrap();
+		let state = self.events.lock().unwrap();
+		QueueSnapshot { seq: state.seq, items: q.clone() }
+	}
+
+	// Aplica limites de banda/horário sem reiniciar; o loop de download lê estes valores a cada bloco
+	pub fn apply_limits(&self, limits: QueueLimits) {
+		*self.limits.lock().unwrap() = limits;
+	}
+
+	// Tamanho esperado de <mod>/<versão> segundo as fontes configuradas, com cache em memória
+	pub async fn estimate_size(&self, mod_name: &str, version: &str) -> Option<u64> {
+		let key = format!("{}@{}", mod_name, version);
+		// Falhas também ficam em cache: o portal oficial custa uma chamada de API e um HEAD por mod
+		if let Some(size) = self.size_cache.lock().unwrap().get(&key) { return *size; }
+		let cfg = super::load_config().await.ok()?;
+		let sources = build_sources(&cfg.download_sources);
+		let size = probe_size(&self.http, &sources, mod_name, version).await;
+		self.size_cache.lock().unwrap().insert(key, size);
+		size
+	}
+
+	fn cached_size(&self, mod_name: &str, version: &str) -> Option<u64> {
+		self.size_cache.lock().unwrap().get(&format!("{}@{}", mod_name, version)).copied().flatten()
+	}
+
+	// Preenche expected_bytes dos itens informados em segundo plano
+	fn spawn_size_estimates(&self, app: &AppHandle, ids: Vec<String>) {
+		let mgr = self.clone();
+		let app = app.clone();
+		tauri::async_runtime::spawn(async move {
+			for id in ids {
+				let Some((mod_name, version)) = mgr.queue.lock().unwrap().iter()
+					.find(|i| i.id == id && i.expected_bytes.is_none())
+					.map(|i| (i.mod_name.clone(), i.version.clone())) else { continue; };
+				let Some(size) = mgr.estimate_size(&mod_name, &version).await else { continue; };
+				if let Some(entry) = mgr.queue.lock().unwrap().iter_mut().find(|i| i.id == id && i.expected_bytes.is_none()) {
+					entry.expected_bytes = Some(size);
+				}
+				mgr.emit_update(&app);
+			}
+			mgr.save_persist();
+		});
+	}
+
+	pub fn estimate(&self) -> QueueEstimate {
+		let q = self.queue.lock().unwrap();
+		let mut estimate = QueueEstimate { total_bytes: 0, remaining_bytes: 0, unknown_items: 0, eta_secs: None };
+		let mut speed = 0.0;
+		for item in q.iter() {
+			let pending = match &item.status {
+				DownloadStatus::Queued | DownloadStatus::Downloading | DownloadStatus::Paused => true,
+				DownloadStatus::Failed(_) => item.next_retry_at.is_some(),
+				_ => false,
+			};
+			if !pending { continue; }
+			if matches!(item.status, DownloadStatus::Downloading) { speed += item.speed_bps; }
+			match item.expected_bytes {
+				Some(bytes) => {
+					estimate.total_bytes += bytes;
+					estimate.remaining_bytes += (bytes as f64 * (1.0 - item.progress.clamp(0.0, 1.0) as f64)) as u64;
+				}
+				None => estimate.unknown_items += 1,
+			}
+		}
+		// Velocidade atual (ou o limite de banda, se não há download ativo) vale para o restante da fila
+		if speed <= 0.0 {
+			speed = self.limits.lock().unwrap().effective_limit(None).unwrap_or(0) as f64;
+		}
+		if speed > 0.0 && estimate.remaining_bytes > 0 {
+			estimate.eta_secs = Some((estimate.remaining_bytes as f64 / speed) as u64);
 		}
-		std::cmp::Ordering::Equal
+		estimate
 	}
 
 	// Remove any queued/paused entries for same mod/profile that are lower than keep_version
 	fn remove_lower_versions(&self, mod_name: &str, profile_name: &str, keep_version: &str) {
+		// Mod fixado/restrito: keep_version pode ser justamente a que não vai ser instalada
+		let holds = super::get_profiles_dir_pub()
+			.map(|d| version_pin::load_holds(&d.join(profile_name)))
+			.unwrap_or_default();
+		if holds.get(mod_name).is_some_and(|h| !h.allows(keep_version)) {
+			return;
+		}
 		if let Ok(mut q) = self.queue.lock() {
 			q.retain(|i| {
 				if i.mod_name == mod_name && i.profile_name == profile_name {
 					// If different version and lower than keep, drop it when not completed/downloading
 					if i.version != keep_version {
 						let is_protected = matches!(i.status, DownloadStatus::Downloading | DownloadStatus::Completed);
-						return is_protected || Self::cmp_versions(&i.version, keep_version) != std::cmp::Ordering::Less;
+						return is_protected || cmp_versions(&i.version, keep_version) != std::cmp::Ordering::Less;
 					}
 				}
 				true
@@ -77,44 +361,67 @@ impl DownloadQueueManager {
 		}
 	}
 
-	// Programmatic enqueue used by dependency resolver/background tasks
-	pub fn enqueue_item_direct(&self, app: &AppHandle, mod_name: String, version: String, profile_name: String) -> String {
-		// Auto-despausar se estiver pausado
+	// Programmatic enqueue used by dependency resolver/background tasks.
+	// Enfileira um plano inteiro como um lote; todos os itens entram antes de qualquer download começar
+	pub fn enqueue_batch(&self, app: &AppHandle, plan: &[(String, String)], profile_name: String) -> String {
 		if let Ok(mut paused) = self.paused_all.lock() {
 			*paused = false;
 		}
-		
-		// Clean lower versions queued for same mod/profile
-		self.remove_lower_versions(&mod_name, &profile_name, &version);
-		let id = Uuid::new_v4().to_string();
+		let batch_id = Uuid::new_v4().to_string();
+		for (mod_name, version) in plan.iter() {
+			self.remove_lower_versions(mod_name, &profile_name, version);
+		}
 		if let Ok(mut q) = self.queue.lock() {
-			q.push(DownloadItem {
-				id: id.clone(),
-				mod_name: mod_name.clone(),
-				version: version.clone(),
-				profile_name: profile_name.clone(),
-				progress: 0.0,
-				status: DownloadStatus::Queued,
-				added_at: chrono::Utc::now().to_rfc3339(),
-				speed_bps: 0.0,
-				eta_secs: None,
-			});
+			for (mod_name, version) in plan.iter() {
+				q.push(DownloadItem {
+					id: Uuid::new_v4().to_string(),
+					mod_name: mod_name.clone(),
+					version: version.clone(),
+					profile_name: profile_name.clone(),
+					progress: 0.0,
+					status: DownloadStatus::Queued,
+					added_at: chrono::Utc::now().to_rfc3339(),
+					speed_bps: 0.0,
+					eta_secs: None,
+					rate_limit_bps: None,
+					attempts: 0,
+					next_retry_at: None,
+					batch_id: Some(batch_id.clone()),
+					staged: false,
+					started_at: None,
+					finished_at: None,
+					outcome: None,
+					expected_bytes: self.cached_size(mod_name, version),
+					priority: 0,
+				});
+			}
 		}
 		self.save_persist();
 		self.emit_update(app);
+		let unknown: Vec<String> = self.queue.lock().unwrap().iter()
+			.filter(|i| i.batch_id.as_deref() == Some(batch_id.as_str()) && i.expected_bytes.is_none())
+			.map(|i| i.id.clone())
+			.collect();
+		self.spawn_size_estimates(app, unknown);
 		DownloadQueueManager::start_next_if_idle_owned(self.clone(), app.clone());
-		id
+		batch_id
 	}
 
 	pub fn start_next_if_idle_owned(manager: DownloadQueueManager, app: AppHandle) {
 		let paused_all = *manager.paused_all.lock().unwrap();
 		if paused_all { return; }
+		// Fora das janelas de horário configuradas: aguardar o agendador periódico
+		if !manager.limits.lock().unwrap().is_within_schedule() { return; }
 		let mut q = manager.queue.lock().unwrap();
 		let is_downloading = q.iter().any(|i| matches!(i.status, DownloadStatus::Downloading));
 		if is_downloading { return; }
-		if let Some(next) = q.iter_mut().find(|i| matches!(i.status, DownloadStatus::Queued)) {
+		let now = chrono::Utc::now();
+		if let Some(next) = q.iter_mut().find(|i| is_ready_to_start(i, now)) {
 			next.status = DownloadStatus::Downloading;
 			next.progress = 0.0;
+			next.next_retry_at = None;
+			next.started_at = Some(chrono::Utc::now().to_rfc3339());
+			next.finished_at = None;
 			let item = next.clone();
 			drop(q);
 			manager.emit_update(&app);
@@ -123,21 +430,77 @@ impl DownloadQueueManager {
 			let mgr_clone = manager.clone();
 			tauri::async_runtime::spawn(async move {
 				let result = stream_download(&mgr_clone, &app_handle, &item).await;
-				let mut q = mgr_clone.queue.lock().unwrap();
-				if let Some(entry) = q.iter_mut().find(|i| i.id == item.id) {
+				let mut retry_in: Option<std::time::Duration> = None;
+				if let Some(entry) = mgr_clone.queue.lock().unwrap().iter_mut().find(|i| i.id == item.id) {
+					entry.speed_bps = 0.0;
+					entry.eta_secs = None;
+					entry.finished_at = Some(chrono::Utc::now().to_rfc3339());
 					match result {
-						Ok(_) => { entry.status = DownloadStatus::Completed; entry.progress = 1.0; entry.speed_bps = 0.0; entry.eta_secs = None; },
-						Err(e) => { entry.status = DownloadStatus::Failed(e); }
+						Ok(outcome) => {
+							entry.status = DownloadStatus::Completed;
+							entry.progress = 1.0;
+							entry.staged = entry.batch_id.is_some();
+							entry.outcome = Some(outcome);
+						},
+						// Janela de horário encerrou: volta para a fila e retoma o .part na próxima janela
+						Err(e) if e.kind == FailureKind::Deferred => { entry.status = DownloadStatus::Queued; entry.progress = 0.0; },
+						// Pausa/cancelamento já definiu o status; só registrar se ainda constar como ativo
+						Err(e) if e.kind == FailureKind::Cancelled => {
+							if matches!(entry.status, DownloadStatus::Downloading) { entry.status = DownloadStatus::Failed(e); }
+						},
+						Err(e) => {
+							entry.attempts += 1;
+							if e.is_transient() && entry.attempts <= MAX_AUTO_RETRIES {
+								let delay = retry_backoff(entry.attempts);
+								entry.next_retry_at = Some((chrono::Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default()).to_rfc3339());
+								retry_in = Some(delay);
+							} else {
+								entry.next_retry_at = None;
+							}
+							entry.status = DownloadStatus::Failed(e);
+						}
 					}
 				}
-				drop(q);
 				mgr_clone.save_persist();
 				mgr_clone.emit_update(&app_handle);
+				if let Some(batch_id) = item.batch_id.as_deref() {
+					download_batch::on_batch_item_finished(&mgr_clone, &app_handle, batch_id).await;
+				}
+				mgr_clone.archive_finished(&app_handle);
+				if let Some(delay) = retry_in {
+					// Acordar a fila quando o backoff terminar
+					let mgr_retry = mgr_clone.clone();
+					let app_retry = app_handle.clone();
+					tauri::async_runtime::spawn(async move {
+						tokio::time::sleep(delay).await;
+						DownloadQueueManager::start_next_if_idle_owned(mgr_retry, app_retry);
+					});
+				}
 				DownloadQueueManager::start_next_if_idle_owned(mgr_clone, app_handle);
 			});
 		}
 	}
 
+	// Move itens concluídos (e já aplicados ao perfil) ou com falha definitiva da fila para o histórico
+	pub(crate) fn archive_finished(&self, app: &AppHandle) {
+		let mut finished: Vec<HistoryEntry> = Vec::new();
+		if let Ok(mut q) = self.queue.lock() {
+			q.retain(|i| {
+				let done = match &i.status {
+					DownloadStatus::Completed => !i.staged,
+					DownloadStatus::Failed(_) => i.next_retry_at.is_none(),
+					_ => false,
+				};
+				if done { finished.push(HistoryEntry::from_item(i)); }
+				!done
+			});
+		}
+		if finished.is_empty() { return; }
+		self.history.record(finished);
+		self.save_persist();
+		self.emit_update(app);
+	}
+
 	fn persist_path_init(&self) -> PathBuf {
 		if let Some(p) = self.persist_path.lock().unwrap().clone() { return p; }
 		let data_dir = dirs::data_dir().unwrap_or(std::env::temp_dir()).join("ModManager");
@@ -147,7 +510,7 @@ impl DownloadQueueManager {
 		path
 	}
 
-	fn save_persist(&self) {
+	pub(crate) fn save_persist(&self) {
 		let path = self.persist_path_init();
 		if let Ok(q) = self.queue.lock() {
 			if let Ok(json) = serde_json::to_string_pretty(&*q) { let _ = fs::write(path, json); }
@@ -181,6 +544,9 @@ pub fn clear_download_queue(app: AppHandle, state: State<DownloadQueueManager>)
 	if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
 	// Clear queue
 	if let Ok(mut q) = state.queue.lock() {
+		for batch_id in q.iter().filter_map(|i| i.batch_id.as_deref()) {
+			download_batch::discard_staging(batch_id);
+		}
 		q.clear();
 	}
 	state.save_persist();
@@ -196,6 +562,10 @@ pub fn enqueue_download(
 	version: String,
 	profile_name: String,
 ) -> Result<String, String> {
+	// Mod fixado/restrito no perfil: recusar já na entrada da fila
+	let profile_dir = super::get_profiles_dir_pub()?.join(&profile_name);
+	version_pin::check(&profile_dir, &mod_name, &version).map_err(|c| c.message)?;
+
 	// Auto-despausar se estiver pausado
 	*state.paused_all.lock().map_err(|_| "Falha ao despausar")? = false;
 	
@@ -211,33 +581,59 @@ pub fn enqueue_download(
 		added_at: chrono::Utc::now().to_rfc3339(),
 		speed_bps: 0.0,
 		eta_secs: None,
+		rate_limit_bps: None,
+		attempts: 0,
+		next_retry_at: None,
+		batch_id: None,
+		staged: false,
+		started_at: None,
+		finished_at: None,
+		outcome: None,
+		expected_bytes: None,
+		priority: 0,
 	};
 	q.push(item);
 	drop(q);
 	state.save_persist();
 	state.emit_update(&app);
+	state.spawn_size_estimates(&app, vec![id.clone()]);
 	DownloadQueueManager::start_next_if_idle_owned(state.inner().clone(), app);
 	Ok(id)
 }
 
 #[tauri::command]
-pub fn get_download_queue(state: State<DownloadQueueManager>) -> Result<Vec<DownloadItem>, String> {
+pub fn get_download_queue(state: State<DownloadQueueManager>, filter: Option<QueueFilter>) -> Result<Vec<DownloadItem>, String> {
 	let q = state.queue.lock().map_err(|_| "Falha ao ler fila")?;
-	Ok(q.clone())
+	Ok(match filter {
+		Some(f) => q.iter().filter(|i| f.matches(i)).cloned().collect(),
+		None => q.clone(),
+	})
+}
+
+#[tauri::command]
+pub fn get_queue_estimate(state: State<DownloadQueueManager>) -> Result<QueueEstimate, String> {
+	Ok(state.estimate())
+}
+
+// Snapshot com seq: eventos com seq menor ou igual já estão incluídos nos itens
+#[tauri::command]
+pub fn get_download_queue_snapshot(state: State<DownloadQueueManager>) -> Result<QueueSnapshot, String> {
+	Ok(state.snapshot())
 }
 
 #[tauri::command]
 pub fn pause_all_downloads(app: AppHandle, state: State<DownloadQueueManager>) -> Result<String, String> {
 	*state.paused_all.lock().map_err(|_| "Falha ao pausar")? = true;
-	// Cancelar download em andamento (rudimentar)
-	if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
 	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
 	for item in q.iter_mut() {
-		if matches!(item.status, DownloadStatus::Downloading | DownloadStatus::Queued) {
+		if is_retry_pending(item) || matches!(item.status, DownloadStatus::Downloading | DownloadStatus::Queued) {
 			item.status = DownloadStatus::Paused;
 		}
+		item.next_retry_at = None;
 	}
 	drop(q);
+	// Cancelar download em andamento depois de marcar Paused, para o .part ser mantido
+	if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
 	state.save_persist();
 	state.emit_update(&app);
 	Ok("Todos os downloads pausados".into())
@@ -262,14 +658,19 @@ pub fn resume_all_downloads(app: AppHandle, state: State<DownloadQueueManager>)
 #[tauri::command]
 pub fn pause_download(app: AppHandle, state: State<DownloadQueueManager>, id: String) -> Result<String, String> {
 	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let mut was_active = false;
 	if let Some(item) = q.iter_mut().find(|i| i.id == id) {
-		if !matches!(item.status, DownloadStatus::Completed | DownloadStatus::Failed(_)) {
+		was_active = matches!(item.status, DownloadStatus::Downloading);
+		if is_retry_pending(item) || !matches!(item.status, DownloadStatus::Completed | DownloadStatus::Failed(_)) {
 			item.status = DownloadStatus::Paused;
 		}
+		item.next_retry_at = None;
 	}
 	drop(q);
-	// Cancelar download em andamento se for o mesmo
-	if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
+	// Cancelar o download em andamento só se for este item; outro ativo seria arquivado como cancelado
+	if was_active {
+		if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
+	}
 	state.save_persist();
 	state.emit_update(&app);
 	Ok("Download pausado".into())
@@ -281,6 +682,8 @@ pub fn resume_download(app: AppHandle, state: State<DownloadQueueManager>, id: S
 	if let Some(item) = q.iter_mut().find(|i| i.id == id) {
 		if matches!(item.status, DownloadStatus::Paused | DownloadStatus::Failed(_)) {
 			item.status = DownloadStatus::Queued;
+			item.attempts = 0;
+			item.next_retry_at = None;
 		}
 	}
 	drop(q);
@@ -293,6 +696,20 @@ pub fn resume_download(app: AppHandle, state: State<DownloadQueueManager>, id: S
 #[tauri::command]
 pub fn remove_from_queue(app: AppHandle, state: State<DownloadQueueManager>, id: String) -> Result<String, String> {
 	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	// Item de lote ainda não aplicado ao perfil: o plano é tudo ou nada, então o lote inteiro
+	// é cancelado (staging descartado) em vez de aplicado sem essa dependência
+	let open_batch = q.iter()
+		.find(|i| i.id == id)
+		.filter(|i| !matches!(i.status, DownloadStatus::Completed) || i.staged)
+		.and_then(|i| i.batch_id.clone());
+	if let Some(batch_id) = open_batch {
+		drop(q);
+		cancel_group(app, state, QueueFilter { batch_id: Some(batch_id), ..Default::default() })?;
+		return Ok("Lote removido da fila".into());
+	}
+	if let Some(item) = q.iter().find(|i| i.id == id && !matches!(i.status, DownloadStatus::Downloading)) {
+		discard_partial(item);
+	}
 	q.retain(|i| i.id != id);
 	drop(q);
 	state.save_persist();
@@ -301,21 +718,203 @@ pub fn remove_from_queue(app: AppHandle, state: State<DownloadQueueManager>, id:
 	Ok("Removido da fila".into())
 }
 
+// Move o item para a posição indicada (0 = topo) sem alterar o status
+fn move_item(state: &DownloadQueueManager, id: &str, position: impl FnOnce(usize) -> usize) -> Result<(), String> {
+	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let pos = q.iter().position(|i| i.id == id).ok_or("Item não encontrado na fila")?;
+	let item = q.remove(pos);
+	let target = position(pos).min(q.len());
+	q.insert(target, item);
+	Ok(())
+}
+
+fn after_reorder(app: AppHandle, state: &State<DownloadQueueManager>) {
+	state.save_persist();
+	state.emit_update(&app);
+	DownloadQueueManager::start_next_if_idle_owned(state.inner().clone(), app);
+}
+
 #[tauri::command]
 pub fn move_to_top(app: AppHandle, state: State<DownloadQueueManager>, id: String) -> Result<String, String> {
+	move_item(&state, &id, |_| 0)?;
+	after_reorder(app, &state);
+	Ok("Movido para o topo".into())
+}
+
+#[tauri::command]
+pub fn move_to_position(app: AppHandle, state: State<DownloadQueueManager>, id: String, position: usize) -> Result<String, String> {
+	move_item(&state, &id, |_| position)?;
+	after_reorder(app, &state);
+	Ok("Item movido".into())
+}
+
+#[tauri::command]
+pub fn move_up(app: AppHandle, state: State<DownloadQueueManager>, id: String) -> Result<String, String> {
+	move_item(&state, &id, |pos| pos.saturating_sub(1))?;
+	after_reorder(app, &state);
+	Ok("Item movido para cima".into())
+}
+
+#[tauri::command]
+pub fn move_down(app: AppHandle, state: State<DownloadQueueManager>, id: String) -> Result<String, String> {
+	move_item(&state, &id, |pos| pos + 1)?;
+	after_reorder(app, &state);
+	Ok("Item movido para baixo".into())
+}
+
+// Define a prioridade e move só esse item: sobe enquanto o anterior tiver prioridade menor,
+// desce enquanto o seguinte tiver maior. A ordem manual do resto da fila é mantida
+#[tauri::command]
+pub fn set_download_priority(app: AppHandle, state: State<DownloadQueueManager>, id: String, priority: i32) -> Result<String, String> {
+	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let mut pos = q.iter().position(|i| i.id == id).ok_or("Item não encontrado na fila")?;
+	q[pos].priority = priority;
+	while pos > 0 && q[pos - 1].priority < priority {
+		q.swap(pos - 1, pos);
+		pos -= 1;
+	}
+	while pos + 1 < q.len() && q[pos + 1].priority > priority {
+		q.swap(pos, pos + 1);
+		pos += 1;
+	}
+	drop(q);
+	after_reorder(app, &state);
+	Ok("Prioridade atualizada".into())
+}
+
+#[tauri::command]
+pub fn sort_queue_by_priority(app: AppHandle, state: State<DownloadQueueManager>) -> Result<String, String> {
+	state.queue.lock().map_err(|_| "Falha ao bloquear fila")?.sort_by(|a, b| b.priority.cmp(&a.priority));
+	after_reorder(app, &state);
+	Ok("Fila ordenada por prioridade".into())
+}
+
+#[tauri::command]
+pub fn get_download_queue_grouped(state: State<DownloadQueueManager>, group_by: QueueGrouping, filter: Option<QueueFilter>) -> Result<Vec<QueueGroup>, String> {
+	let filter = filter.unwrap_or_default();
+	let q = state.queue.lock().map_err(|_| "Falha ao ler fila")?;
+	let mut groups: Vec<QueueGroup> = Vec::new();
+	for item in q.iter().filter(|i| filter.matches(i)) {
+		let key = match group_by {
+			QueueGrouping::Profile => Some(item.profile_name.clone()),
+			QueueGrouping::Batch => item.batch_id.clone(),
+		};
+		let group = match groups.iter().position(|g| g.key == key) {
+			Some(idx) => &mut groups[idx],
+			None => {
+				groups.push(QueueGroup { key, items: Vec::new(), queued: 0, downloading: 0, paused: 0, failed: 0 });
+				groups.last_mut().unwrap()
+			}
+		};
+		match item.status {
+			DownloadStatus::Queued => group.queued += 1,
+			DownloadStatus::Downloading => group.downloading += 1,
+			DownloadStatus::Paused => group.paused += 1,
+			DownloadStatus::Failed(_) => group.failed += 1,
+			_ => {}
+		}
+		group.items.push(item.clone());
+	}
+	Ok(groups)
+}
+
+// .part de um item pausado/adiado que sai da fila; o do item ativo é removido pelo próprio download
+fn discard_partial(item: &DownloadItem) {
+	// Staging de lote é descartado inteiro
+	if item.batch_id.is_some() { return; }
+	let Ok(profiles_dir) = super::get_profiles_dir_pub() else { return };
+	if let Ok(mods_dir) = super::resolve_profile_mods_dir(&profiles_dir.join(&item.profile_name)) {
+		let _ = fs::remove_file(mods_dir.join(format!("{}_{}.zip.part", item.mod_name, item.version)));
+	}
+}
+
+// Cancela o download em andamento apenas se ele pertencer à seleção
+fn cancel_active_if(state: &DownloadQueueManager, filter: &QueueFilter) {
+	let active_matches = state.queue.lock().unwrap().iter()
+		.any(|i| matches!(i.status, DownloadStatus::Downloading) && filter.matches(i));
+	if active_matches {
+		if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
+	}
+}
+
+// Pausa só os itens de um perfil/lote; o restante da fila continua
+#[tauri::command]
+pub fn pause_group(app: AppHandle, state: State<DownloadQueueManager>, filter: QueueFilter) -> Result<String, String> {
+	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let active_matches = q.iter().any(|i| matches!(i.status, DownloadStatus::Downloading) && filter.matches(i));
+	let mut count = 0;
+	for item in q.iter_mut().filter(|i| filter.matches(i)) {
+		// Falha aguardando nova tentativa também pausa, para o resume_group recolocá-la na fila
+		if is_retry_pending(item) || matches!(item.status, DownloadStatus::Downloading | DownloadStatus::Queued) {
+			item.status = DownloadStatus::Paused;
+			count += 1;
+		}
+		item.next_retry_at = None;
+	}
+	drop(q);
+	// Só depois de marcar Paused: o download interrompido guarda o .part para retomar
+	if active_matches {
+		if let Some(tx) = state.cancel_tx.lock().unwrap().take() { let _ = tx.send(()); }
+	}
+	state.save_persist();
+	state.emit_update(&app);
+	DownloadQueueManager::start_next_if_idle_owned(state.inner().clone(), app);
+	Ok(format!("{} downloads pausados", count))
+}
+
+#[tauri::command]
+pub fn resume_group(app: AppHandle, state: State<DownloadQueueManager>, filter: QueueFilter) -> Result<String, String> {
+	*state.paused_all.lock().map_err(|_| "Falha ao retomar")? = false;
 	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
-	if let Some(pos) = q.iter().position(|i| i.id == id) {
-		let mut item = q.remove(pos);
-		if !matches!(item.status, DownloadStatus::Completed) {
+	let mut count = 0;
+	for item in q.iter_mut().filter(|i| filter.matches(i)) {
+		if matches!(item.status, DownloadStatus::Paused) {
 			item.status = DownloadStatus::Queued;
+			count += 1;
 		}
-		q.insert(0, item);
 	}
 	drop(q);
 	state.save_persist();
 	state.emit_update(&app);
 	DownloadQueueManager::start_next_if_idle_owned(state.inner().clone(), app);
-	Ok("Movido para o topo".into())
+	Ok(format!("{} downloads retomados", count))
+}
+
+// Remove os itens não concluídos da seleção; lotes afetados são descartados inteiros
+#[tauri::command]
+pub fn cancel_group(app: AppHandle, state: State<DownloadQueueManager>, filter: QueueFilter) -> Result<String, String> {
+	cancel_active_if(&state, &filter);
+	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let cancelled = |i: &DownloadItem| filter.matches(i) && (!matches!(i.status, DownloadStatus::Completed) || i.staged);
+	let batches: HashSet<String> = q.iter().filter(|i| cancelled(i)).filter_map(|i| i.batch_id.clone()).collect();
+	for batch_id in batches.iter() {
+		download_batch::discard_staging(batch_id);
+	}
+	// Itens de um lote descartado que ainda não entraram no perfil saem junto
+	let in_dropped_batch = |i: &DownloadItem| i.batch_id.as_ref().is_some_and(|b| batches.contains(b))
+		&& (!matches!(i.status, DownloadStatus::Completed) || i.staged);
+	for item in q.iter().filter(|i| cancelled(i) && !matches!(i.status, DownloadStatus::Downloading)) {
+		discard_partial(item);
+	}
+	let before = q.len();
+	q.retain(|i| !cancelled(i) && !in_dropped_batch(i));
+	let count = before - q.len();
+	drop(q);
+	state.save_persist();
+	state.emit_update(&app);
+	DownloadQueueManager::start_next_if_idle_owned(state.inner().clone(), app);
+	Ok(format!("{} downloads cancelados", count))
+}
+
+#[tauri::command]
+pub fn set_download_rate_limit(app: AppHandle, state: State<DownloadQueueManager>, id: String, limit_bps: Option<u64>) -> Result<String, String> {
+	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
+	let item = q.iter_mut().find(|i| i.id == id).ok_or("Item não encontrado na fila")?;
+	item.rate_limit_bps = limit_bps.filter(|l| *l > 0);
+	drop(q);
+	state.save_persist();
+	state.emit_update(&app);
+	Ok("Limite de banda atualizado".into())
 }
 
 #[tauri::command]
@@ -326,7 +925,11 @@ pub fn cancel_all_downloads(app: AppHandle, state: State<DownloadQueueManager>)
 	
 	// Remover todos os downloads que não estão completos
 	let mut q = state.queue.lock().map_err(|_| "Falha ao bloquear fila")?;
-	q.retain(|item| matches!(item.status, DownloadStatus::Completed));
+	// Itens em staging ainda não foram aplicados ao perfil: descartar junto com o lote
+	for batch_id in q.iter().filter(|i| !matches!(i.status, DownloadStatus::Completed) || i.staged).filter_map(|i| i.batch_id.as_deref()) {
+		download_batch::discard_staging(batch_id);
+	}
+	q.retain(|item| matches!(item.status, DownloadStatus::Completed) && !item.staged);
 	drop(q);
 	
 	state.save_persist();
@@ -334,37 +937,163 @@ pub fn cancel_all_downloads(app: AppHandle, state: State<DownloadQueueManager>)
 	Ok("Todos os downloads cancelados".into())
 }
 
-async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item: &DownloadItem) -> Result<(), String> {
+// SHA-1 de um arquivo (bloqueante)
+pub(crate) fn file_sha1_blocking(path: &Path) -> Option<String> {
+	use sha1::{Digest, Sha1};
+	use std::io::Read;
+	let mut file = fs::File::open(path).ok()?;
+	let mut hasher = Sha1::new();
+	let mut buf = vec![0u8; 64 * 1024];
+	loop {
+		let n = file.read(&mut buf).ok()?;
+		if n == 0 { break; }
+		hasher.update(&buf[..n]);
+	}
+	Some(format!("{:x}", hasher.finalize()))
+}
+
+// SHA-1 do arquivo baixado, calculado fora do runtime async
+pub(crate) async fn file_sha1(path: &Path) -> Option<String> {
+	let path = path.to_path_buf();
+	tokio::task::spawn_blocking(move || file_sha1_blocking(&path)).await.ok().flatten()
+}
+
+async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item: &DownloadItem) -> Result<DownloadOutcome, DownloadFailure> {
 	// Paths e arquivo de destino
-	let profiles_dir = super::get_profiles_dir_pub()?;
+	let profiles_dir = super::get_profiles_dir_pub().map_err(DownloadFailure::other)?;
 	let profile_dir = profiles_dir.join(&item.profile_name);
-	let mods_dir = profile_dir.join("mods");
-	if !mods_dir.exists() { fs::create_dir_all(&mods_dir).map_err(|e| e.to_string())?; }
+	// Itens de lote vão para o staging; o perfil só é alterado no commit do lote
+	let target_dir = match item.batch_id.as_deref() {
+		Some(batch_id) => download_batch::staging_dir(batch_id),
+		None => super::resolve_profile_mods_dir(&profile_dir).map_err(DownloadFailure::disk)?,
+	};
+	if !target_dir.exists() { fs::create_dir_all(&target_dir).map_err(DownloadFailure::disk)?; }
+
+	// Mod instalado como pasta/symlink (desenvolvimento) não é substituído por um zip
+	let mods_dir = super::resolve_profile_mods_dir(&profile_dir).map_err(DownloadFailure::disk)?;
+	if let Some(existing) = mod_install::find_unmanaged(&mods_dir, &item.mod_name) {
+		return Err(DownloadFailure::other(format!(
+			"{} já está instalado como pasta ou link ({}); remova-o antes de baixar o zip",
+			item.mod_name,
+			existing.display()
+		)));
+	}
+	// O pin pode ter sido criado depois do item entrar na fila
+	if let Err(conflict) = version_pin::check(&profile_dir, &item.mod_name, &item.version) {
+		return Err(DownloadFailure::other(conflict.message));
+	}
 
-	// URL de download e destino
-	let anticache = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs_f64();
-	let url = format!("https://mods-storage.re146.dev/{}/{}.zip?anticache={}", item.mod_name, item.version, anticache);
 	let file_name = format!("{}_{}.zip", item.mod_name, item.version);
-	let dest_path = mods_dir.join(&file_name);
+	let dest_path = target_dir.join(&file_name);
+	// Baixa ao lado, em <arquivo>.part: um zip já instalado com o mesmo nome só é trocado depois de validado
+	let part_path = target_dir.join(format!("{}.part", file_name));
+
+	let app_cfg = super::load_config().await.map_err(DownloadFailure::other)?;
 
-	// Remover versões anteriores
-	if let Ok(entries) = fs::read_dir(&mods_dir) { for entry in entries.flatten() {
-		let entry_name = entry.file_name().to_string_lossy().to_string();
-		if entry_name.starts_with(&format!("{}_", item.mod_name)) && entry_name.ends_with(".zip") && entry_name != file_name {
-			let _ = fs::remove_file(entry.path());
+	// Tentar as fontes configuradas em ordem, passando para a próxima em caso de falha
+	let sources = build_sources(&app_cfg.download_sources);
+	let mut errors: Vec<DownloadFailure> = Vec::new();
+	let mut fetched: Option<(u64, String, mod_archive::ArchiveInfoJson)> = None;
+	for source in sources.iter() {
+		let result = match source.locate(&manager.http, &item.mod_name, &item.version).await {
+			Ok(DownloadTarget::Local(src)) => copy_local(&src, &part_path).await,
+			Ok(DownloadTarget::Stored(object)) => mod_store::link_object(&object, &part_path)
+				.and_then(|_| fs::metadata(&part_path).map(|m| m.len()))
+				.map_err(DownloadFailure::disk),
+			Ok(DownloadTarget::Http(url)) => stream_from_url(manager, app, item, &url, &part_path).await,
+			Err(e) => Err(e),
+		};
+		// Zip corrompido ou de outro mod (espelho quebrado) conta como falha da fonte: tenta a próxima
+		let result = match result {
+			Ok(bytes) => mod_archive::validate_mod_zip_async(&part_path, &item.mod_name, &item.version).await.map(|info| (bytes, info)),
+			Err(e) => Err(e),
+		};
+		match result {
+			Ok((bytes, info)) => { fetched = Some((bytes, source.id(), info)); break; }
+			Err(e) if e.is_interruption() => return Err(e),
+			Err(e) => {
+				let _ = tokio::fs::remove_file(&part_path).await;
+				let _ = app.emit("download-queue:source-failed", &serde_json::json!({
+					"id": item.id,
+					"mod_name": item.mod_name,
+					"version": item.version,
+					"source": source.id(),
+					"message": e.message,
+					"kind": e.kind,
+				}));
+				errors.push(DownloadFailure { message: format!("{}: {}", source.id(), e.message), ..e });
+			}
 		}
-	}}
+	}
+	let Some((bytes, source_id, info)) = fetched else {
+		// Classificar pela falha mais "recuperável": se alguma fonte falhou de forma transitória, vale tentar de novo
+		let summary = errors.iter().map(|e| e.message.clone()).collect::<Vec<_>>().join("; ");
+		let representative = errors.iter().find(|e| e.is_transient()).or(errors.last()).cloned()
+			.unwrap_or_else(|| DownloadFailure::other("Nenhuma fonte de download configurada"));
+		return Err(DownloadFailure {
+			message: format!("Nenhuma fonte conseguiu baixar o mod: {}", summary),
+			..representative
+		});
+	};
+	// Só agora o zip anterior de mesmo nome sai (com a referência no store) e o novo assume o lugar
+	mod_store::detach(&dest_path);
+	fs::rename(&part_path, &dest_path).map_err(DownloadFailure::disk)?;
+	let outcome = DownloadOutcome { bytes, source: source_id, sha1: file_sha1(&dest_path).await };
 
-	let client = Client::builder()
-		.pool_max_idle_per_host(1)
-		.tcp_keepalive(std::time::Duration::from_secs(30))
-		.build()
-		.map_err(|e| format!("Erro ao criar cliente HTTP: {}", e))?;
+	// Lote: listas JSON são atualizadas no commit
+	if item.batch_id.is_some() {
+		return Ok(outcome);
+	}
+
+	// Com o zip novo validado, as versões anteriores vão para o arquivo do perfil; um download
+	// que falhe deixa o mod instalado intacto (lotes arquivam no commit)
+	version_archive::archive_other_versions(&profile_dir, &target_dir, &item.mod_name, &file_name, app_cfg.archived_versions_per_mod);
+
+	// Arquivo passa a ser um vínculo para o store compartilhado
+	if let Err(e) = mod_store::adopt(&dest_path, &item.mod_name, &item.version, outcome.sha1.as_deref()) {
+		println!("Aviso: {} v{} não entrou no store: {}", item.mod_name, item.version, e);
+	}
+
+	// Atualizar listas JSON de controle (metadados do info.json, sem nova consulta ao portal)
+	let previous_version = super::add_mod_to_lists(&profile_dir, &file_name, &info)
+		.map_err(DownloadFailure::disk)?;
+	hooks::fire_installed(app, previous_version, hooks::HookContext {
+		mod_name: Some(item.mod_name.clone()),
+		version: Some(item.version.clone()),
+		profile_name: Some(item.profile_name.clone()),
+		file_path: Some(dest_path.to_string_lossy().to_string()),
+		mods_dir: Some(target_dir.to_string_lossy().to_string()),
+		..Default::default()
+	});
+
+	Ok(outcome)
+}
+
+// Copia um zip de uma fonte local, verificando antes o espaço livre no destino
+async fn copy_local(src: &Path, dest_path: &Path) -> Result<u64, DownloadFailure> {
+	let size = tokio::fs::metadata(src).await.map_err(DownloadFailure::disk)?.len();
+	if let Some(dir) = dest_path.parent() {
+		disk_space::ensure_free_space(dir, size)?;
+	}
+	tokio::fs::copy(src, dest_path).await.map_err(DownloadFailure::disk)
+}
+
+// Baixa uma URL para dest_path com progresso, retomada via Range e cancelamento
+// O sinal de cancelamento também serve à pausa; só o item pausado guarda o download parcial
+async fn discard_unless_paused(manager: &DownloadQueueManager, id: &str, part_path: &Path) {
+	let paused = manager.queue.lock().unwrap().iter().any(|i| i.id == id && matches!(i.status, DownloadStatus::Paused));
+	if !paused {
+		let _ = tokio::fs::remove_file(part_path).await;
+	}
+}
+
+async fn stream_from_url(manager: &DownloadQueueManager, app: &AppHandle, item: &DownloadItem, url: &str, dest_path: &Path) -> Result<u64, DownloadFailure> {
+	let http = &manager.http;
 
 	// Preflight: tentar obter tamanho total e suporte a Accept-Ranges
 	let mut total_known: Option<u64> = None;
 	let mut supports_range: bool = false;
-	if let Ok(head) = client.head(&url).send().await {
+	if let Ok(head) = http.send(http.client().head(url).timeout(API_TIMEOUT)).await {
 		if head.status().is_success() {
 			if let Some(len) = head.headers().get(reqwest::header::CONTENT_LENGTH) {
 				if let Ok(s) = len.to_str() { if let Ok(v) = s.parse::<u64>() { total_known = Some(v); } }
@@ -376,14 +1105,14 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 	}
 
 	// Tentar retomar em caso de travamento usando Range
-	async fn start_request_with_range(client: &Client, url: &str, start_at: u64) -> Result<(Option<u64>, Option<u64>, reqwest::Response, bool), String> {
-		let mut req = client.get(url);
+	async fn start_request_with_range(http: &HttpService, url: &str, start_at: u64) -> Result<(Option<u64>, Option<u64>, HostResponse, bool), DownloadFailure> {
+		let mut req = http.client().get(url);
 		if start_at > 0 {
 			req = req.header(reqwest::header::RANGE, format!("bytes={}-", start_at));
 		}
-		let resp = req.send().await.map_err(|e| format!("Erro no download: {}", e))?;
+		let resp = http.send(req).await.map_err(DownloadFailure::network)?;
 		if !(resp.status().is_success() || resp.status() == reqwest::StatusCode::PARTIAL_CONTENT) {
-			return Err(format!("Erro HTTP: {}", resp.status()));
+			return Err(DownloadFailure::http(resp.status()));
 		}
 		let mut total_all = None;
 		if let Some(hv) = resp.headers().get(reqwest::header::CONTENT_RANGE) {
@@ -402,21 +1131,44 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 		Ok((total_all, remaining, resp, honored))
 	}
 
+	// .part deixado por pausa ou fim da janela de horário: continuar de onde parou
+	let existing = tokio::fs::metadata(dest_path).await.map(|m| m.len()).unwrap_or(0);
+	let resume_from = if supports_range && total_known.is_none_or(|t| existing < t) { existing } else { 0 };
+
 	let mut total_remaining: Option<u64> = None;
 	// Stream atual
 	let mut stream;
-	// Iniciar primeira requisição
-	let (tot_all, remaining, resp0, _honored0) = start_request_with_range(&client, &url, 0).await?;
+	// Iniciar primeira requisição; se o Range for recusado, recomeçar do zero
+	let (tot_all, remaining, resp0, honored0) = match start_request_with_range(http, url, resume_from).await {
+		Err(_) if resume_from > 0 => start_request_with_range(http, url, 0).await?,
+		first => first?,
+	};
+	let resumed_at = if honored0 { resume_from } else { 0 };
 	if total_known.is_none() { total_known = tot_all; }
 	total_remaining = remaining;
+	// Verificar espaço livre antes de criar o arquivo, em vez de deixar um zip truncado com o disco cheio
+	if let (Some(expected), Some(dir)) = (total_known.map(|t| t.saturating_sub(resumed_at)).or(total_remaining), dest_path.parent()) {
+		disk_space::ensure_free_space(dir, expected)?;
+	}
+	// Tamanho real substitui a estimativa feita ao enfileirar
+	if let Some(total) = total_known {
+		if let Some(entry) = manager.queue.lock().unwrap().iter_mut().find(|i| i.id == item.id) {
+			entry.expected_bytes = Some(total);
+		}
+		manager.size_cache.lock().unwrap().insert(format!("{}@{}", item.mod_name, item.version), Some(total));
+	}
 	stream = resp0.bytes_stream();
-	let file = tokio::fs::File::create(&dest_path).await.map_err(|e| e.to_string())?;
+	let file = if resumed_at > 0 {
+		tokio::fs::OpenOptions::new().append(true).open(dest_path).await
+	} else {
+		tokio::fs::File::create(dest_path).await
+	}.map_err(DownloadFailure::disk)?;
 	let mut file = tokio::io::BufWriter::with_capacity(1024 * 1024, file); // 1 MiB buffer
 
 	let start = Instant::now();
-	let mut downloaded: u64 = 0;
+	let mut downloaded: u64 = resumed_at;
 	let mut last_emit = Instant::now();
-	let mut last_emit_bytes: u64 = 0;
+	let mut last_emit_bytes: u64 = resumed_at;
 	let min_emit_interval = std::time::Duration::from_millis(500); // Aumentado para 500ms para melhor estabilidade
 	let min_emit_bytes: u64 = 256 * 1024; // 256KB
 	
@@ -431,17 +1183,44 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 		let mut c = manager.cancel_tx.lock().unwrap();
 		*c = Some(tx);
 	}
+	let mut limiter = RateLimiter::default();
+	let mut throttle_delay: Option<std::time::Duration> = None;
 
 	loop {
+		// Respeitar limite de banda: esperar antes do próximo bloco, ainda atendendo cancelamento
+		if let Some(delay) = throttle_delay.take() {
+			tokio::select! {
+				_ = tokio::time::sleep(delay) => {},
+				_ = &mut rx => {
+					use tokio::io::AsyncWriteExt;
+					let _ = file.flush().await;
+					discard_unless_paused(manager, &item.id, dest_path).await;
+					return Err(DownloadFailure::cancelled());
+				}
+			}
+		}
 		tokio::select! {
 			chunk = tokio::time::timeout(std::time::Duration::from_secs(20), stream.next()) => {
 				match chunk {
 					Ok(Some(Ok(bytes))) => {
 						use tokio::io::AsyncWriteExt;
 						let slice = bytes.as_ref();
-						file.write_all(slice).await.map_err(|e| e.to_string())?;
+						file.write_all(slice).await.map_err(DownloadFailure::disk)?;
 						downloaded += slice.len() as u64;
 
+						// Limites lidos a cada bloco para que mudanças valham sem reiniciar
+						let item_limit = manager.queue.lock().unwrap().iter().find(|i| i.id == item.id).and_then(|i| i.rate_limit_bps);
+						let (limit, in_schedule) = {
+							let limits = manager.limits.lock().unwrap();
+							(limits.effective_limit(item_limit), limits.is_within_schedule())
+						};
+						// Fora da janela: o .part fica para ser retomado com Range na próxima
+						if !in_schedule {
+							file.flush().await.map_err(DownloadFailure::disk)?;
+							return Err(DownloadFailure::deferred());
+						}
+						throttle_delay = limiter.delay_for(slice.len() as u64, limit);
+
 						// Throttle progress updates to reduce CPU and UI churn
 						let should_emit = last_emit.elapsed() >= min_emit_interval
 							|| (downloaded - last_emit_bytes) >= min_emit_bytes;
@@ -476,7 +1255,7 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 							} else {
 								// Fallback para velocidade total se não há amostras suficientes
 								let elapsed_total = start.elapsed().as_secs_f64();
-								if elapsed_total > 0.0 { downloaded as f64 / elapsed_total } else { 0.0 }
+								if elapsed_total > 0.0 { (downloaded - resumed_at) as f64 / elapsed_total } else { 0.0 }
 							};
 							
 							let progress = if total_all>0 { downloaded as f32 / total_all as f32 } else { 0.0 };
@@ -489,24 +1268,24 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 								entry.eta_secs = eta;
 							}
 							drop(q);
-							manager.emit_update(app);
+							manager.emit_progress(app, &item.id);
 							last_emit = Instant::now();
 							last_emit_bytes = downloaded;
 						}
 					},
-					Ok(Some(Err(e))) => return Err(format!("Erro no stream: {}", e)),
+					Ok(Some(Err(e))) => return Err(DownloadFailure::network(format!("stream interrompido: {}", e.without_url()))),
 					Ok(None) => break,
 					Err(_) => {
 						// Timeout sem receber bytes: tentar retomar com Range
 						// Flush buffer antes de retomar
 						use tokio::io::AsyncWriteExt;
-						file.flush().await.map_err(|e| e.to_string())?;
+						file.flush().await.map_err(DownloadFailure::disk)?;
 						if retries >= max_retries {
-							return Err("Tempo esgotado ao baixar (muitas tentativas de retomada)".into());
+							return Err(DownloadFailure::network("tempo esgotado ao baixar (muitas tentativas de retomada)"));
 						}
 						retries += 1;
 						if supports_range {
-							if let Ok((tot_all, remaining, resp_new, honored)) = start_request_with_range(&client, &url, downloaded).await {
+							if let Ok((tot_all, remaining, resp_new, honored)) = start_request_with_range(http, url, downloaded).await {
 								if honored {
 									if total_known.is_none() { total_known = tot_all; }
 									total_remaining = remaining;
@@ -520,26 +1299,28 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 						}
 						// Fallback: reiniciar download completo (truncate)
 						drop(file);
-						let base_file = tokio::fs::File::create(&dest_path).await.map_err(|e| e.to_string())?;
+						let base_file = tokio::fs::File::create(dest_path).await.map_err(DownloadFailure::disk)?;
 						file = tokio::io::BufWriter::with_capacity(1024 * 1024, base_file);
 						downloaded = 0;
 						last_emit = Instant::now();
 						last_emit_bytes = 0;
-						if let Ok((tot_all, remaining, resp_new, _)) = start_request_with_range(&client, &url, 0).await {
+						if let Ok((tot_all, remaining, resp_new, _)) = start_request_with_range(http, url, 0).await {
 							if total_known.is_none() { total_known = tot_all; }
 							total_remaining = remaining;
 							stream = resp_new.bytes_stream();
 							continue;
 						} else {
-							return Err("Falha ao reiniciar download".into());
+							return Err(DownloadFailure::network("falha ao reiniciar download"));
 						}
 					}
 				}
 			},
 			_ = &mut rx => {
-				// cancelado - remover arquivo parcial se existir
-				let _ = tokio::fs::remove_file(&dest_path).await;
-				return Err("Cancelado".into());
+				// Pausa mantém o .part para retomar; cancelamento remove
+				use tokio::io::AsyncWriteExt;
+				let _ = file.flush().await;
+				discard_unless_paused(manager, &item.id, dest_path).await;
+				return Err(DownloadFailure::cancelled());
 			}
 		}
 		// Yield occasionally if neither branch did heavy work to reduce CPU
@@ -548,11 +1329,7 @@ async fn stream_download(manager: &DownloadQueueManager, app: &AppHandle, item:
 
 	// Ensure buffered writer flushes to disk
 	use tokio::io::AsyncWriteExt;
-	file.flush().await.map_err(|e| e.to_string())?;
-
-	// Atualizar listas JSON de controle
-	let factorio_version = super::get_factorio_version_for(item.mod_name.clone(), item.version.clone()).await?;
-	super::add_mod_to_lists(&profile_dir, &item.mod_name, &item.version, &file_name, &factorio_version)?;
+	file.flush().await.map_err(DownloadFailure::disk)?;
 
-	Ok(())
+	Ok(downloaded)
 }
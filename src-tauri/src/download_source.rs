@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::download_failure::{DownloadFailure, FailureKind};
+use crate::endpoints::encode_name;
+use crate::http_client::{HttpService, API_TIMEOUT};
+
+// Destino concreto de um arquivo de mod depois que uma fonte o localizou
+#[derive(Debug, Clone)]
+pub enum DownloadTarget {
+	Http(String),
+	Local(PathBuf),
+	// Objeto do store compartilhado: vinculado (hardlink) em vez de copiado
+	Stored(PathBuf),
+}
+
+// Uma origem capaz de localizar o zip de <mod>/<versão>.
+// A fila tenta as fontes na ordem configurada e passa para a próxima em caso de falha.
+pub trait DownloadSource: Send + Sync {
+	// Identificador estável usado em logs e eventos
+	fn id(&self) -> String;
+	fn locate<'a>(&'a self, http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>>;
+}
+
+// Configuração persistida em AppConfig.download_sources (a ordem da lista é a ordem de tentativa)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DownloadSourceConfig {
+	// Portal oficial (mods.factorio.com) usando as credenciais de player-data.json
+	Official,
+	// Espelho público re146 (endereço em AppConfig.endpoints)
+	Re146Mirror,
+	// Espelho definido pelo usuário; aceita {name}, {version} e {file_name}
+	MirrorTemplate { url_template: String },
+	// Diretório local ou compartilhamento de rede contendo <name>_<version>.zip
+	LocalDirectory { path: String },
+}
+
+pub fn default_download_sources() -> Vec<DownloadSourceConfig> {
+	vec![DownloadSourceConfig::Re146Mirror, DownloadSourceConfig::Official]
+}
+
+// O store compartilhado entre perfis é sempre consultado antes das fontes configuradas
+pub fn build_sources(configs: &[DownloadSourceConfig]) -> Vec<Box<dyn DownloadSource>> {
+	std::iter::once(Box::new(crate::mod_store::StoreSource) as Box<dyn DownloadSource>)
+		.chain(configs.iter().map(|cfg| -> Box<dyn DownloadSource> {
+			match cfg {
+				DownloadSourceConfig::Official => Box::new(OfficialPortalSource),
+				DownloadSourceConfig::Re146Mirror => Box::new(Re146MirrorSource),
+				DownloadSourceConfig::MirrorTemplate { url_template } => Box::new(TemplateMirrorSource { url_template: url_template.clone() }),
+				DownloadSourceConfig::LocalDirectory { path } => Box::new(LocalDirectorySource { path: PathBuf::from(path) }),
+			}
+		}))
+		.collect()
+}
+
+// Tamanho esperado do zip segundo a primeira fonte que o localizar (Content-Length do HEAD ou tamanho local)
+pub async fn probe_size(http: &HttpService, sources: &[Box<dyn DownloadSource>], mod_name: &str, version: &str) -> Option<u64> {
+	for source in sources.iter() {
+		match source.locate(http, mod_name, version).await {
+			Ok(DownloadTarget::Local(path)) | Ok(DownloadTarget::Stored(path)) => return std::fs::metadata(path).ok().map(|m| m.len()),
+			Ok(DownloadTarget::Http(url)) => {
+				if let Some(len) = head_content_length(http, &url).await { return Some(len); }
+			}
+			Err(_) => continue,
+		}
+	}
+	None
+}
+
+pub async fn head_content_length(http: &HttpService, url: &str) -> Option<u64> {
+	let response = http.send(http.client().head(url).timeout(API_TIMEOUT)).await.ok()?;
+	if !response.status().is_success() { return None; }
+	response.headers()
+		.get(reqwest::header::CONTENT_LENGTH)?
+		.to_str().ok()?
+		.parse::<u64>().ok()
+}
+
+pub struct Re146MirrorSource;
+
+impl DownloadSource for Re146MirrorSource {
+	fn id(&self) -> String { "re146".into() }
+
+	fn locate<'a>(&'a self, http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>> {
+		Box::pin(async move {
+			let anticache = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs_f64();
+			Ok(DownloadTarget::Http(format!(
+				"{}?anticache={}",
+				http.endpoints().re146_zip(mod_name, version), anticache
+			)))
+		})
+	}
+}
+
+pub struct TemplateMirrorSource {
+	pub url_template: String,
+}
+
+impl DownloadSource for TemplateMirrorSource {
+	fn id(&self) -> String { format!("mirror:{}", self.url_template) }
+
+	fn locate<'a>(&'a self, _http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>> {
+		Box::pin(async move {
+			if !self.url_template.contains("{name}") && !self.url_template.contains("{file_name}") {
+				return Err(DownloadFailure::other(format!("Modelo de URL inválido (falta {{name}} ou {{file_name}}): {}", self.url_template)));
+			}
+			let url = self.url_template
+				.replace("{file_name}", &encode_name(&format!("{}_{}.zip", mod_name, version)))
+				.replace("{name}", &encode_name(mod_name))
+				.replace("{version}", version);
+			Ok(DownloadTarget::Http(url))
+		})
+	}
+}
+
+pub struct LocalDirectorySource {
+	pub path: PathBuf,
+}
+
+impl DownloadSource for LocalDirectorySource {
+	fn id(&self) -> String { format!("local:{}", self.path.display()) }
+
+	fn locate<'a>(&'a self, _http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>> {
+		Box::pin(async move {
+			let candidate = self.path.join(format!("{}_{}.zip", mod_name, version));
+			if candidate.is_file() {
+				Ok(DownloadTarget::Local(candidate))
+			} else {
+				Err(DownloadFailure::new(FailureKind::NotFound, format!("Arquivo não encontrado: {}", candidate.display())))
+			}
+		})
+	}
+}
+
+pub struct OfficialPortalSource;
+
+#[derive(Debug, Deserialize)]
+struct PlayerData {
+	#[serde(rename = "service-username", default)]
+	service_username: Option<String>,
+	#[serde(rename = "service-token", default)]
+	service_token: Option<String>,
+}
+
+// Lê service-username/service-token do player-data.json do Factorio
+fn load_portal_credentials() -> Result<(String, String), String> {
+	let path = super::get_factorio_data_dir()?.join("player-data.json");
+	let content = std::fs::read_to_string(&path)
+		.map_err(|e| format!("Erro ao ler {}: {}", path.display(), e))?;
+	let data: PlayerData = serde_json::from_str(&content)
+		.map_err(|e| format!("Erro ao parsear player-data.json: {}", e))?;
+	match (data.service_username, data.service_token) {
+		(Some(user), Some(token)) if !user.is_empty() && !token.is_empty() => Ok((user, token)),
+		_ => Err("Credenciais do portal ausentes em player-data.json (faça login no jogo)".into()),
+	}
+}
+
+impl DownloadSource for OfficialPortalSource {
+	fn id(&self) -> String { "official".into() }
+
+	fn locate<'a>(&'a self, http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>> {
+		Box::pin(async move {
+			let (username, token) = load_portal_credentials().map_err(DownloadFailure::other)?;
+			let endpoints = http.endpoints();
+			let url = endpoints.mod_full_api(mod_name);
+			let response = http.send(http.api_get(&url))
+				.await
+				.map_err(DownloadFailure::network)?;
+			if !response.status().is_success() {
+				return Err(DownloadFailure::http(response.status()));
+			}
+			let full: super::ModFullResponse = response
+				.json()
+				.await
+				.map_err(|e| DownloadFailure::other(format!("Erro ao parsear JSON: {}", e)))?;
+			let release = full.releases.iter().find(|r| r.version == version)
+				.ok_or_else(|| DownloadFailure::new(FailureKind::NotFound, format!("Versão {} não encontrada para {}", version, mod_name)))?;
+			// Credenciais codificadas na query: & + = % no token não podem quebrar a URL
+			let url = reqwest::Url::parse_with_params(
+				&endpoints.portal_download(&release.download_url),
+				&[("username", username.as_str()), ("token", token.as_str())],
+			).map_err(|e| DownloadFailure::other(format!("URL de download inválida: {}", e)))?;
+			Ok(DownloadTarget::Http(url.to_string()))
+		})
+	}
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+// Endereços base dos serviços externos (AppConfig.endpoints); permitem espelho próprio,
+// proxy de cache interno ou um servidor local nos testes de integração
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Endpoints {
+	// Portal de mods: API (/api/mods) e downloads oficiais
+	pub portal_url: String,
+	// Imagens/thumbnails do portal (caminhos /assets/...)
+	pub assets_url: String,
+	// Espelho de zips no formato <base>/<name>/<version>.zip
+	pub re146_mirror_url: String,
+}
+
+impl Default for Endpoints {
+	fn default() -> Self {
+		Self {
+			portal_url: "https://mods.factorio.com".into(),
+			assets_url: "https://assets-mod.factorio.com".into(),
+			re146_mirror_url: "https://mods-storage.re146.dev".into(),
+		}
+	}
+}
+
+fn base(url: &str) -> &str {
+	url.trim().trim_end_matches('/')
+}
+
+pub fn encode_name(mod_name: &str) -> String {
+	mod_name.replace(' ', "%20")
+}
+
+impl Endpoints {
+	// Lista paginada de mods
+	pub fn mods_api(&self) -> String {
+		format!("{}/api/mods", base(&self.portal_url))
+	}
+
+	pub fn mod_api(&self, mod_name: &str) -> String {
+		format!("{}/api/mods/{}", base(&self.portal_url), encode_name(mod_name))
+	}
+
+	pub fn mod_full_api(&self, mod_name: &str) -> String {
+		format!("{}/api/mods/{}/full", base(&self.portal_url), encode_name(mod_name))
+	}
+
+	// download_url das releases é relativo ao portal (/download/...)
+	pub fn portal_download(&self, download_url: &str) -> String {
+		format!("{}{}", base(&self.portal_url), download_url)
+	}
+
+	pub fn asset(&self, path: &str) -> String {
+		format!("{}{}", base(&self.assets_url), path)
+	}
+
+	// Thumbnails da API vêm como caminho relativo (/assets/...); URLs completas passam intactas
+	pub fn asset_url(&self, path: &str) -> String {
+		if path.starts_with("http://") || path.starts_with("https://") {
+			path.to_string()
+		} else {
+			self.asset(path)
+		}
+	}
+
+	pub fn re146_zip(&self, mod_name: &str, version: &str) -> String {
+		format!("{}/{}/{}.zip", base(&self.re146_mirror_url), encode_name(mod_name), version)
+	}
+
+	pub fn validate(&self) -> Result<(), String> {
+		for (label, url) in [("portal", &self.portal_url), ("assets", &self.assets_url), ("re146", &self.re146_mirror_url)] {
+			let parsed = reqwest::Url::parse(base(url)).map_err(|e| format!("Endereço {} inválido ({}): {}", label, url, e))?;
+			if !matches!(parsed.scheme(), "http" | "https") {
+				return Err(format!("Endereço {} deve usar http ou https: {}", label, url));
+			}
+		}
+		Ok(())
+	}
+}
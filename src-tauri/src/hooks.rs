@@ -0,0 +1,170 @@
+use std::process::Stdio;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+// Momento em que um hook roda
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+	ModInstalled,
+	ModUpdated,
+	ModRemoved,
+	BatchFinished,
+}
+
+// Comando configurado pelo usuário (AppConfig.hooks); roda no shell do sistema
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HookConfig {
+	pub event: HookEvent,
+	pub command: String,
+	#[serde(default = "default_enabled")]
+	pub enabled: bool,
+	// Tempo máximo de execução; o processo é encerrado depois disso
+	#[serde(default)]
+	pub timeout_secs: Option<u64>,
+}
+
+fn default_enabled() -> bool { true }
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+// Trecho final de stdout/stderr incluído no evento
+const OUTPUT_TAIL: usize = 2000;
+
+// Dados repassados ao hook como variáveis de ambiente FMM_*
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HookContext {
+	pub mod_name: Option<String>,
+	pub version: Option<String>,
+	pub previous_version: Option<String>,
+	pub profile_name: Option<String>,
+	pub file_path: Option<String>,
+	pub mods_dir: Option<String>,
+	pub batch_id: Option<String>,
+	// "committed" ou "failed" em BatchFinished
+	pub batch_status: Option<String>,
+}
+
+impl HookContext {
+	fn env_vars(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+		let event_name = serde_json::to_value(event).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+		let mut vars = vec![("FMM_EVENT", event_name)];
+		let optional = [
+			("FMM_MOD_NAME", &self.mod_name),
+			("FMM_MOD_VERSION", &self.version),
+			("FMM_PREVIOUS_VERSION", &self.previous_version),
+			("FMM_PROFILE", &self.profile_name),
+			("FMM_FILE_PATH", &self.file_path),
+			("FMM_MODS_DIR", &self.mods_dir),
+			("FMM_BATCH_ID", &self.batch_id),
+			("FMM_BATCH_STATUS", &self.batch_status),
+		];
+		vars.extend(optional.into_iter().filter_map(|(k, v)| v.clone().map(|v| (k, v))));
+		vars
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct HookResult {
+	event: HookEvent,
+	command: String,
+	context: HookContext,
+	success: bool,
+	exit_code: Option<i32>,
+	timed_out: bool,
+	duration_ms: u64,
+	stdout: String,
+	stderr: String,
+	error: Option<String>,
+}
+
+fn tail(bytes: &[u8]) -> String {
+	let text = String::from_utf8_lossy(bytes);
+	let start = text.char_indices().rev().nth(OUTPUT_TAIL).map(|(i, _)| i).unwrap_or(0);
+	text[start..].to_string()
+}
+
+fn shell_command(command: &str) -> tokio::process::Command {
+	#[cfg(windows)]
+	{
+		let mut cmd = tokio::process::Command::new("cmd");
+		cmd.args(["/C", command]);
+		cmd
+	}
+	#[cfg(not(windows))]
+	{
+		let mut cmd = tokio::process::Command::new("sh");
+		cmd.args(["-c", command]);
+		cmd
+	}
+}
+
+async fn run_hook(hook: &HookConfig, event: HookEvent, context: &HookContext) -> HookResult {
+	let started = std::time::Instant::now();
+	let mut result = HookResult {
+		event,
+		command: hook.command.clone(),
+		context: context.clone(),
+		success: false,
+		exit_code: None,
+		timed_out: false,
+		duration_ms: 0,
+		stdout: String::new(),
+		stderr: String::new(),
+		error: None,
+	};
+	let mut cmd = shell_command(&hook.command);
+	cmd.envs(context.env_vars(event))
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.kill_on_drop(true);
+	if let Some(dir) = context.mods_dir.as_deref() {
+		cmd.current_dir(dir);
+	}
+	let timeout = hook.timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_TIMEOUT);
+	match cmd.spawn() {
+		Ok(child) => match tokio::time::timeout(timeout, child.wait_with_output()).await {
+			Ok(Ok(output)) => {
+				result.success = output.status.success();
+				result.exit_code = output.status.code();
+				result.stdout = tail(&output.stdout);
+				result.stderr = tail(&output.stderr);
+			}
+			Ok(Err(e)) => result.error = Some(format!("Erro ao aguardar o hook: {}", e)),
+			// O future é descartado e kill_on_drop encerra o processo
+			Err(_) => {
+				result.timed_out = true;
+				result.error = Some(format!("Hook excedeu {}s e foi encerrado", timeout.as_secs()));
+			}
+		},
+		Err(e) => result.error = Some(format!("Falha ao iniciar o hook: {}", e)),
+	}
+	result.duration_ms = started.elapsed().as_millis() as u64;
+	result
+}
+
+// Dispara em segundo plano os hooks configurados para o evento; cada resultado vira um evento "hooks:result"
+pub fn fire(app: &AppHandle, event: HookEvent, context: HookContext) {
+	let app = app.clone();
+	tauri::async_runtime::spawn(async move {
+		let Ok(cfg) = crate::load_config().await else { return; };
+		// Hooks do mesmo evento rodam em sequência, na ordem configurada
+		for hook in cfg.hooks.iter().filter(|h| h.enabled && h.event == event && !h.command.trim().is_empty()) {
+			let result = run_hook(hook, event, &context).await;
+			if !result.success {
+				println!("Hook {:?} '{}' falhou: {:?} {:?}", event, hook.command, result.exit_code, result.error);
+			}
+			let _ = app.emit("hooks:result", &result);
+		}
+	});
+}
+
+// Instalação nova ou atualização, conforme havia versão anterior na lista do perfil
+pub fn fire_installed(app: &AppHandle, previous_version: Option<String>, context: HookContext) {
+	let event = match previous_version.as_deref() {
+		Some(prev) if Some(prev) != context.version.as_deref() => HookEvent::ModUpdated,
+		_ => HookEvent::ModInstalled,
+	};
+	fire(app, event, HookContext { previous_version, ..context });
+}
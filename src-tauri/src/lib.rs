@@ -8,6 +8,29 @@ use dirs;
 use regex::Regex;
 mod download_queue;
 mod dependency_resolver;
+mod download_source;
+mod download_limits;
+mod download_failure;
+mod download_batch;
+mod download_history;
+mod http_client;
+mod disk_space;
+mod mod_store;
+mod mod_archive;
+mod endpoints;
+mod hooks;
+mod mod_reconcile;
+mod mod_install;
+mod update_checker;
+mod version_pin;
+mod version_archive;
+mod mod_version;
+mod property_tree;
+mod mod_settings;
+mod setting_definitions;
+mod changelog;
+mod mod_locale;
+use http_client::HttpService;
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::process::Command;
@@ -20,14 +43,65 @@ struct AppConfig {
     // Perfil selecionado atualmente (folder_name do perfil)
     #[serde(default)]
     selected_profile: Option<String>,
+    // Fontes de download, na ordem em que a fila deve tentá-las
+    #[serde(default = "download_source::default_download_sources")]
+    download_sources: Vec<download_source::DownloadSourceConfig>,
+    // Limite global de banda da fila (bytes/s); None = sem limite
+    #[serde(default)]
+    bandwidth_limit_bps: Option<u64>,
+    // Janelas de horário em que a fila pode baixar; vazia = sempre
+    #[serde(default)]
+    download_windows: Vec<download_limits::DownloadWindow>,
+    // Dias que o histórico de downloads é mantido (0 = para sempre)
+    #[serde(default = "default_history_retention_days")]
+    history_retention_days: u32,
+    // Proxy HTTP(S) para todas as requisições; None = conexão direta
+    #[serde(default)]
+    proxy_url: Option<String>,
+    // Hosts que não passam pelo proxy (ex.: "localhost", ".empresa.local", "10.0.0.0/8")
+    #[serde(default)]
+    no_proxy: Vec<String>,
+    // Bundle PEM com CAs extras confiáveis (proxies corporativos com inspeção TLS)
+    #[serde(default)]
+    ca_bundle_path: Option<String>,
+    // Endereços do portal, assets e espelho re146
+    #[serde(default)]
+    endpoints: endpoints::Endpoints,
+    // Comandos executados após instalar/atualizar/remover mods e ao fim de um lote da fila
+    #[serde(default)]
+    hooks: Vec<hooks::HookConfig>,
+    // Intervalo da verificação de atualizações em segundo plano; None/0 = desligada
+    #[serde(default)]
+    update_check_interval_minutes: Option<u32>,
+    // Versões substituídas guardadas por mod em <perfil>/archive para rollback (0 = apagar)
+    #[serde(default = "default_archived_versions_per_mod")]
+    archived_versions_per_mod: u32,
+    // Idioma dos nomes/descrições de mods (pasta de locale do Factorio, ex.: "pt-BR"); None = idioma do sistema
+    #[serde(default)]
+    locale: Option<String>,
 }
 
+fn default_history_retention_days() -> u32 { 30 }
+fn default_archived_versions_per_mod() -> u32 { 3 }
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             cache_expiry_hours: 24,
             game_exe_path: None,
             selected_profile: None,
+            download_sources: download_source::default_download_sources(),
+            bandwidth_limit_bps: None,
+            download_windows: Vec::new(),
+            history_retention_days: default_history_retention_days(),
+            proxy_url: None,
+            no_proxy: Vec::new(),
+            ca_bundle_path: None,
+            endpoints: endpoints::Endpoints::default(),
+            hooks: Vec::new(),
+            update_check_interval_minutes: None,
+            archived_versions_per_mod: default_archived_versions_per_mod(),
+            locale: None,
         }
     }
 }
@@ -86,6 +160,8 @@ struct ModFullData {
     #[serde(default)]
     images: Option<Vec<FullImage>>,
     #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
     downloads_count: Option<u64>,
     #[serde(default)]
     score: Option<f64>,
@@ -191,7 +267,7 @@ struct FactorioModList {
     mods: Vec<FactorioModListEntry>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 struct InternalModEntry {
     name: String,
     version: String,
@@ -199,6 +275,66 @@ struct InternalModEntry {
     file_name: String,
     download_date: String,
     factorio_version: String,
+    // Metadados do info.json do zip, para as telas de mods instalados funcionarem offline
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    // Zip, pasta descompactada ou symlink; conferido no disco a cada listagem
+    #[serde(default)]
+    kind: mod_install::InstallKind,
+    // Mod fixado na versão instalada: nunca é atualizado ou substituído sem ação explícita
+    #[serde(default)]
+    pinned: bool,
+    // Restrição para atualizações (ex.: "< 2.0" ou ">= 1.2, < 1.3"); ver version_pin
+    #[serde(default)]
+    version_constraint: Option<String>,
+    // mod-name/mod-description do locale/ do mod, no idioma em `locale`; relidos quando o idioma muda
+    #[serde(default)]
+    localised_title: Option<String>,
+    #[serde(default)]
+    localised_description: Option<String>,
+    #[serde(default)]
+    locale: Option<String>,
+}
+
+impl InternalModEntry {
+    fn from_info(info: &mod_archive::ArchiveInfoJson, file_name: &str, enabled: bool) -> Self {
+        InternalModEntry {
+            name: info.name.clone(),
+            version: info.version.clone(),
+            enabled,
+            file_name: file_name.to_string(),
+            download_date: Utc::now().to_rfc3339(),
+            factorio_version: info.factorio_version_or_default(),
+            title: info.title.clone(),
+            author: info.author.clone(),
+            description: info.description.clone(),
+            dependencies: info.dependencies.clone(),
+            kind: mod_install::InstallKind::Zip,
+            pinned: false,
+            version_constraint: None,
+            localised_title: None,
+            localised_description: None,
+            locale: None,
+        }
+    }
+
+    // Entradas gravadas antes dos metadados locais, ou pastas/links editados fora do app
+    fn apply_info(&mut self, info: &mod_archive::ArchiveInfoJson) {
+        self.version = info.version.clone();
+        self.factorio_version = info.factorio_version_or_default();
+        self.title = info.title.clone();
+        self.author = info.author.clone();
+        self.description = info.description.clone();
+        self.dependencies = info.dependencies.clone();
+        // Arquivos mudaram: nomes traduzidos são lidos de novo na próxima listagem
+        self.locale = None;
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -369,6 +505,7 @@ fn greet(name: &str) -> String {
 
 #[tauri::command]
 async fn fetch_factorio_mods(
+    http: tauri::State<'_, HttpService>,
     page: Option<u32>,
     page_size: Option<u32>,
     hide_deprecated: Option<bool>,
@@ -376,8 +513,7 @@ async fn fetch_factorio_mods(
     sort: Option<String>,
     sort_order: Option<String>,
 ) -> Result<ModsResponse, ApiError> {
-    let client = reqwest::Client::new();
-    let mut url = "https://mods.factorio.com/api/mods".to_string();
+    let mut url = http.endpoints().mods_api();
     
     // Construir parâmetros de query
     let mut params = Vec::new();
@@ -413,11 +549,12 @@ async fn fetch_factorio_mods(
     
     println!("Fazendo requisição para: {}", url);
     
-    match client.get(&url).send().await {
+    match http.send(http.api_get(&url)).await {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<ModsResponse>().await {
-                    Ok(mods_data) => {
+                    Ok(mut mods_data) => {
+                        absolutize_thumbnails(&mut mods_data.results, &http.endpoints());
                         println!("Sucesso! Encontrados {} mods", mods_data.results.len());
                         Ok(mods_data)
                     }
@@ -450,17 +587,19 @@ async fn fetch_factorio_mods(
 }
 
 #[tauri::command]
-async fn fetch_all_mods() -> Result<CachedModsData, ApiError> {
-    let client = reqwest::Client::new();
-    let url = "https://mods.factorio.com/api/mods?page_size=max";
+async fn fetch_all_mods(http: tauri::State<'_, HttpService>) -> Result<CachedModsData, ApiError> {
+    let url = format!("{}?page_size=max", http.endpoints().mods_api());
     
     println!("Fazendo requisição para: {}", url);
     
-    match client.get(url).send().await {
+    // Lista completa é grande: timeout maior que o padrão de API
+    let request = http.api_get(&url).timeout(std::time::Duration::from_secs(180));
+    match http.send(request).await {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<ModsResponse>().await {
-                    Ok(mods_data) => {
+                    Ok(mut mods_data) => {
+                        absolutize_thumbnails(&mut mods_data.results, &http.endpoints());
                         println!("Sucesso! Baixados {} mods", mods_data.results.len());
                         let cached_data = CachedModsData {
                             timestamp: Utc::now().to_rfc3339(),
@@ -524,7 +663,7 @@ async fn save_mods_cache(data: CachedModsData) -> Result<String, ApiError> {
 }
 
 #[tauri::command]
-async fn load_mods_cache() -> Result<CachedModsData, ApiError> {
+async fn load_mods_cache(http: tauri::State<'_, HttpService>) -> Result<CachedModsData, ApiError> {
     let cache_path = get_cache_file_path()?;
     
     if !cache_path.exists() {
@@ -539,10 +678,12 @@ async fn load_mods_cache() -> Result<CachedModsData, ApiError> {
         details: Some(e.to_string()),
     })?;
     
-    let cache_data: CachedModsData = serde_json::from_str(&content).map_err(|e| ApiError {
+    let mut cache_data: CachedModsData = serde_json::from_str(&content).map_err(|e| ApiError {
         error: "Erro ao deserializar cache".to_string(),
         details: Some(e.to_string()),
     })?;
+    // Caches antigos guardaram o caminho relativo
+    absolutize_thumbnails(&mut cache_data.results, &http.endpoints());
     
     println!("Cache carregado com {} mods", cache_data.results.len());
     Ok(cache_data)
@@ -787,16 +928,20 @@ async fn search_mods(
     })
 }
 
+// Thumbnail da API oficial é relativo ao host de assets configurado; o frontend recebe a URL pronta
+fn absolutize_thumbnails(mods: &mut [FactorioMod], endpoints: &endpoints::Endpoints) {
+    for mod_data in mods.iter_mut() {
+        mod_data.thumbnail = mod_data.thumbnail.take().map(|t| endpoints.asset_url(&t));
+    }
+}
+
 // Função para buscar thumbnail de um mod específico da API re146.dev
-async fn fetch_mod_thumbnail(mod_name: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
-    let enc_name = mod_name.replace(' ', "%20");
-    let url = format!("https://mods.factorio.com/api/mods/{}", enc_name);
+async fn fetch_mod_thumbnail(http: &HttpService, mod_name: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let endpoints = http.endpoints();
+    let url = endpoints.mod_api(mod_name);
     
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
-    
-    let response = client.get(&url).send().await?;
+    let request = http.api_get(&url).timeout(std::time::Duration::from_secs(10));
+    let response = http.send(request).await?;
     
     if !response.status().is_success() {
         return Ok(None);
@@ -814,7 +959,7 @@ async fn fetch_mod_thumbnail(mod_name: &str) -> Result<Option<String>, Box<dyn s
     // Fallback para o campo thumbnail (que é um path relativo)
     if let Some(thumbnail_path) = enhanced_info.thumbnail {
         if thumbnail_path.starts_with("/assets/") {
-            let full_url = format!("https://assets-mod.factorio.com{}", thumbnail_path);
+            let full_url = endpoints.asset(&thumbnail_path);
             return Ok(Some(full_url));
         }
     }
@@ -823,8 +968,8 @@ async fn fetch_mod_thumbnail(mod_name: &str) -> Result<Option<String>, Box<dyn s
 }
 
 #[tauri::command]
-async fn fetch_mod_thumbnail_cmd(mod_name: String) -> Result<Option<String>, String> {
-    match fetch_mod_thumbnail(&mod_name).await {
+async fn fetch_mod_thumbnail_cmd(http: tauri::State<'_, HttpService>, mod_name: String) -> Result<Option<String>, String> {
+    match fetch_mod_thumbnail(&http, &mod_name).await {
         Ok(thumbnail) => Ok(thumbnail),
         Err(e) => {
             eprintln!("Erro ao buscar thumbnail para {}: {}", mod_name, e);
@@ -834,7 +979,7 @@ async fn fetch_mod_thumbnail_cmd(mod_name: String) -> Result<Option<String>, Str
 }
 
 #[tauri::command]
-async fn update_popular_thumbnails(limit: Option<usize>) -> Result<String, String> {
+async fn update_popular_thumbnails(http: tauri::State<'_, HttpService>, limit: Option<usize>) -> Result<String, String> {
     let data_dir = get_cache_dir().map_err(|e| format!("Erro ao obter diretório de dados: {:?}", e))?;
     let cache_path = data_dir.join("mods-data.json");
     
@@ -861,7 +1006,7 @@ async fn update_popular_thumbnails(limit: Option<usize>) -> Result<String, Strin
     println!("Iniciando atualização de thumbnails para {} mods populares...", update_limit);
     
     for mod_data in mods_to_process {
-        match fetch_mod_thumbnail(&mod_data.name).await {
+        match fetch_mod_thumbnail(&http, &mod_data.name).await {
             Ok(Some(thumbnail)) => {
                 mod_data.enhanced_thumbnail = Some(thumbnail);
                 mod_data.thumbnail_loaded = true;
@@ -1178,6 +1323,8 @@ async fn delete_profile(folder_name: String) -> Result<String, String> {
 
     fs::remove_dir_all(&profile_dir)
         .map_err(|e| format!("Erro ao deletar perfil: {}", e))?;
+    // Zips do perfil deixam de segurar objetos do store (mods_path customizado fica intacto)
+    mod_store::release_under(&profile_dir);
 
     Ok("Perfil deletado com sucesso".to_string())
 }
@@ -1196,10 +1343,15 @@ fn get_config_path() -> Result<PathBuf, String> {
     Ok(mod_manager_dir.join(".config"))
 }
 
+// Diretório de dados do Factorio (AppData\Roaming\Factorio)
+fn get_factorio_data_dir() -> Result<PathBuf, String> {
+    let roaming_dir = dirs::config_dir().ok_or("Não foi possível encontrar o diretório de configuração")?;
+    Ok(roaming_dir.join("Factorio"))
+}
+
 // Caminho do diretório mods do Factorio (AppData\Roaming\Factorio\mods)
 fn get_factorio_mods_dir() -> Result<PathBuf, String> {
-    let roaming_dir = dirs::config_dir().ok_or("Não foi possível encontrar o diretório de configuração")?;
-    let factorio_dir = roaming_dir.join("Factorio");
+    let factorio_dir = get_factorio_data_dir()?;
     if !factorio_dir.exists() {
         fs::create_dir_all(&factorio_dir).map_err(|e| format!("Erro ao criar diretório Factorio: {}", e))?;
     }
@@ -1336,7 +1488,7 @@ async fn load_config() -> Result<AppConfig, String> {
     
     if !config_path.exists() {
         let default_config = AppConfig::default();
-        save_config(default_config.clone()).await?;
+        write_config(&default_config)?;
         return Ok(default_config);
     }
     
@@ -1349,16 +1501,51 @@ async fn load_config() -> Result<AppConfig, String> {
     Ok(config)
 }
 
-// Função para salvar configurações
-#[tauri::command]
-async fn save_config(config: AppConfig) -> Result<String, String> {
+fn write_config(config: &AppConfig) -> Result<(), String> {
     let config_path = get_config_path()?;
     
-    let config_json = serde_json::to_string_pretty(&config)
+    let config_json = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Erro ao serializar configuração: {}", e))?;
     
     fs::write(&config_path, config_json)
-        .map_err(|e| format!("Erro ao salvar configuração: {}", e))?;
+        .map_err(|e| format!("Erro ao salvar configuração: {}", e))
+}
+
+// Converte a configuração do app nos limites aplicados pela fila de downloads
+fn queue_limits_from(config: &AppConfig) -> download_limits::QueueLimits {
+    download_limits::QueueLimits {
+        global_limit_bps: config.bandwidth_limit_bps,
+        windows: config.download_windows.clone(),
+    }
+}
+
+// Converte a configuração do app nas opções de rede do cliente HTTP compartilhado
+fn network_settings_from(config: &AppConfig) -> http_client::NetworkSettings {
+    http_client::NetworkSettings {
+        proxy_url: config.proxy_url.clone(),
+        no_proxy: config.no_proxy.clone(),
+        ca_bundle_path: config.ca_bundle_path.clone(),
+    }
+}
+
+// Função para salvar configurações
+#[tauri::command]
+async fn save_config(
+    app: tauri::AppHandle,
+    http: tauri::State<'_, HttpService>,
+    queue: tauri::State<'_, download_queue::DownloadQueueManager>,
+    config: AppConfig,
+) -> Result<String, String> {
+    // Proxy/CA/endereços inválidos são recusados antes de gravar, mantendo o cliente atual
+    config.endpoints.validate()?;
+    http.apply_network_settings(&network_settings_from(&config))?;
+    http.set_endpoints(config.endpoints.clone())?;
+    write_config(&config)?;
+    
+    // Aplicar limites de banda/horário imediatamente na fila
+    queue.apply_limits(queue_limits_from(&config));
+    queue.history.set_retention_days(config.history_retention_days);
+    download_queue::DownloadQueueManager::start_next_if_idle_owned(queue.inner().clone(), app);
     
     Ok("Configuração salva com sucesso".to_string())
 }
@@ -1374,7 +1561,8 @@ async fn get_selected_profile() -> Result<Option<String>, String> {
 async fn set_selected_profile(folder_name: String) -> Result<String, String> {
     let mut cfg = load_config().await?;
     cfg.selected_profile = Some(folder_name);
-    save_config(cfg).await
+    write_config(&cfg)?;
+    Ok("Configuração salva com sucesso".to_string())
 }
 
 // Valida se um caminho existe no sistema de arquivos
@@ -1428,11 +1616,14 @@ async fn get_cache_size() -> Result<String, String> {
 
 // Função para buscar detalhes completos de um mod (todas as versões)
 #[tauri::command]
-async fn fetch_mod_details(mod_name: String) -> Result<ModDetails, String> {
-    let enc_name = mod_name.replace(' ', "%20");
-    let url = format!("https://mods.factorio.com/api/mods/{}/full", enc_name);
+async fn fetch_mod_details(http: tauri::State<'_, HttpService>, mod_name: String) -> Result<ModDetails, String> {
+    fetch_mod_details_internal(&http, mod_name).await
+}
+
+pub(crate) async fn fetch_mod_details_internal(http: &HttpService, mod_name: String) -> Result<ModDetails, String> {
+    let url = http.endpoints().mod_full_api(&mod_name);
     
-    let response = reqwest::get(&url)
+    let response = http.send(http.api_get(&url))
         .await
         .map_err(|e| format!("Erro na requisição: {}", e))?;
 
@@ -1467,15 +1658,14 @@ async fn fetch_mod_details(mod_name: String) -> Result<ModDetails, String> {
 
 // Full mod data proxy (avoids CORS in frontend)
 #[tauri::command]
-async fn fetch_mod_full(mod_name: String) -> Result<ModFullData, String> {
-    let enc_name = mod_name.replace(' ', "%20");
-    let url = format!("https://mods.factorio.com/api/mods/{}/full", enc_name);
-    let client = reqwest::Client::builder()
-        .build()
-        .map_err(|e| format!("Erro ao criar cliente: {}", e))?;
-    let response = client
-        .get(&url)
-        .send()
+async fn fetch_mod_full(http: tauri::State<'_, HttpService>, mod_name: String) -> Result<ModFullData, String> {
+    fetch_mod_full_internal(&http, mod_name).await
+}
+
+pub(crate) async fn fetch_mod_full_internal(http: &HttpService, mod_name: String) -> Result<ModFullData, String> {
+    let url = http.endpoints().mod_full_api(&mod_name);
+    let response = http
+        .send(http.api_get(&url))
         .await
         .map_err(|e| format!("Erro na requisição: {}", e))?;
 
@@ -1483,10 +1673,13 @@ async fn fetch_mod_full(mod_name: String) -> Result<ModFullData, String> {
         return Err(format!("Erro HTTP: {}", response.status()));
     }
 
-    response
+    let mut data = response
         .json::<ModFullData>()
         .await
-        .map_err(|e| format!("Erro ao parsear JSON: {}", e))
+        .map_err(|e| format!("Erro ao parsear JSON: {}", e))?;
+    let endpoints = http.endpoints();
+    data.thumbnail = data.thumbnail.map(|t| endpoints.asset_url(&t));
+    Ok(data)
 }
 
 // Funções para gerenciar os arquivos JSON de controle de mods
@@ -1582,11 +1775,10 @@ fn save_internal_mod_list(profile_dir: &PathBuf, internal_list: &InternalModList
 
 pub(crate) fn add_mod_to_lists(
     profile_dir: &PathBuf,
-    mod_name: &str,
-    version: &str,
     file_name: &str,
-    factorio_version: &str,
-) -> Result<(), String> {
+    info: &mod_archive::ArchiveInfoJson,
+) -> Result<Option<String>, String> {
+    let mod_name = info.name.as_str();
     // Carregar listas existentes
     let mut factorio_list = load_factorio_mod_list(profile_dir)?;
     let mut internal_list = load_internal_mod_list(profile_dir)?;
@@ -1604,30 +1796,33 @@ pub(crate) fn add_mod_to_lists(
         save_factorio_mod_list(profile_dir, &factorio_list)?;
     }
     
-    // Remover versão antiga do mod da lista interna (se existir)
+    // Remover versão antiga do mod da lista interna (se existir); pin e restrição continuam valendo
+    let previous = internal_list.mods.iter().find(|m| m.name == mod_name).cloned();
+    let previous_version = previous.as_ref().map(|m| m.version.clone());
     internal_list.mods.retain(|m| m.name != mod_name);
     
     // Adicionar nova versão à lista interna
-    internal_list.mods.push(InternalModEntry {
-        name: mod_name.to_string(),
-        version: version.to_string(),
-        enabled: true,
-        file_name: file_name.to_string(),
-        download_date: Utc::now().to_rfc3339(),
-        factorio_version: factorio_version.to_string(),
-    });
+    let mut entry = InternalModEntry::from_info(info, file_name, true);
+    if let Some(previous) = previous {
+        entry.pinned = previous.pinned;
+        entry.version_constraint = previous.version_constraint;
+    }
+    internal_list.mods.push(entry);
     
     // Atualizar timestamp
     internal_list.last_updated = Utc::now().to_rfc3339();
     
     save_internal_mod_list(profile_dir, &internal_list)?;
     
-    Ok(())
+    // Versão substituída (None = instalação nova)
+    Ok(previous_version)
 }
 
 // Função para download de mod específico para um perfil
 // Internal function used by queue and wrapper command
 pub(crate) async fn download_mod_to_profile_internal(
+    app: &tauri::AppHandle,
+    http: &HttpService,
     mod_name: String, 
     version: String, 
     profile_name: String
@@ -1637,46 +1832,88 @@ pub(crate) async fn download_mod_to_profile_internal(
     let mods_dir = resolve_profile_mods_dir(&profile_dir)?;
     
     // Diretório de mods já garantido por resolve_profile_mods_dir
+    // A versão do Factorio e os demais metadados vêm do info.json do próprio zip
+
+    // Mod instalado como pasta/symlink (desenvolvimento) não é substituído por um zip
+    if let Some(existing) = mod_install::find_unmanaged(&mods_dir, &mod_name) {
+        return Err(format!("{} já está instalado como pasta ou link ({}); remova-o antes de baixar o zip", mod_name, existing.display()));
+    }
+    // Mod fixado/restrito não é substituído sem o usuário soltar o pin antes
+    version_pin::check(&profile_dir, &mod_name, &version).map_err(|c| c.message)?;
 
-    // Primeiro, buscar detalhes do mod para obter a versão do Factorio
-    let mod_details = fetch_mod_details(mod_name.clone()).await?;
-    
-    // Encontrar a release específica para obter a versão do Factorio
-    let target_release = mod_details.releases.iter()
-        .find(|r| r.version == version)
-        .ok_or_else(|| format!("Versão {} não encontrada para o mod {}", version, mod_name))?;
-    
-    let factorio_version = &target_release.factorio_version;
-    
-    // Construir URL de download
-    let anticache = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs_f64();
-    
-    let enc_name = mod_name.replace(' ', "%20");
-    let download_url = format!(
-        "https://mods-storage.re146.dev/{}/{}.zip?anticache={}", 
-        enc_name, version, anticache
-    );
-    
     // Nome do arquivo
     let file_name = format!("{}_{}.zip", mod_name, version);
     let file_path = mods_dir.join(&file_name);
+    // Download vai para <arquivo>.part; o zip instalado de mesmo nome só é trocado depois da validação
+    let part_path = mods_dir.join(format!("{}.part", file_name));
     
-    // Remover versão anterior do mesmo mod (se existir)
-    if let Ok(entries) = fs::read_dir(&mods_dir) {
-        for entry in entries.flatten() {
-            let entry_name = entry.file_name().to_string_lossy().to_string();
-            if entry_name.starts_with(&format!("{}_", mod_name)) && entry_name.ends_with(".zip") && entry_name != file_name {
-                let _ = fs::remove_file(entry.path());
-                println!("Removida versão anterior: {}", entry_name);
+    let app_cfg = load_config().await?;
+    
+    // Fazer download tentando cada fonte configurada, na ordem
+    let mut errors: Vec<String> = Vec::new();
+    let mut info: Option<mod_archive::ArchiveInfoJson> = None;
+    for source in download_source::build_sources(&app_cfg.download_sources) {
+        let result = match source.locate(http, &mod_name, &version).await {
+            Ok(download_source::DownloadTarget::Local(src)) => fs::copy(&src, &part_path)
+                .map(|_| ())
+                .map_err(|e| format!("Erro ao copiar arquivo: {}", e)),
+            Ok(download_source::DownloadTarget::Stored(object)) => mod_store::link_object(&object, &part_path)
+                .map(|_| ())
+                .map_err(|e| format!("Erro ao vincular arquivo do store: {}", e)),
+            Ok(download_source::DownloadTarget::Http(url)) => fetch_url_to_file(http, &url, &part_path).await,
+            Err(e) => Err(e.to_string()),
+        };
+        // Zip inválido não pode chegar ao mod-list.json: descartar e tentar a próxima fonte
+        let result = match result {
+            Ok(()) => mod_archive::validate_mod_zip_async(&part_path, &mod_name, &version).await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let _ = fs::remove_file(&part_path);
+        }
+        match result {
+            Ok(validated) => { info = Some(validated); break; }
+            Err(e) => {
+                println!("Fonte {} falhou para {} v{}: {}", source.id(), mod_name, version, e);
+                errors.push(format!("{}: {}", source.id(), e));
             }
         }
     }
-    
-    // Fazer download
-    let response = reqwest::get(&download_url)
+    let Some(info) = info else {
+        return Err(format!("Nenhuma fonte conseguiu baixar {} v{}: {}", mod_name, version, errors.join("; ")));
+    };
+
+    // Zip validado: sai o anterior de mesmo nome (e sua referência no store), entra o novo
+    mod_store::detach(&file_path);
+    fs::rename(&part_path, &file_path).map_err(|e| format!("Erro ao mover {}: {}", file_name, e))?;
+
+    // Só com o zip novo validado a versão anterior (se existir) vai para o arquivo do perfil;
+    // se o download falhar o mod instalado continua intacto
+    version_archive::archive_other_versions(&profile_dir, &mods_dir, &mod_name, &file_name, app_cfg.archived_versions_per_mod);
+
+    // Compartilhar o zip com outros perfis pelo store global
+    if let Err(e) = mod_store::adopt(&file_path, &mod_name, &version, None) {
+        println!("Aviso: {} v{} não entrou no store: {}", mod_name, version, e);
+    }
+
+    // Atualizar arquivos JSON de controle
+    let previous_version = add_mod_to_lists(&profile_dir, &file_name, &info)
+        .map_err(|e| format!("Erro ao atualizar listas de mods: {}", e))?;
+    hooks::fire_installed(app, previous_version, hooks::HookContext {
+        mod_name: Some(mod_name.clone()),
+        version: Some(version.clone()),
+        profile_name: Some(profile_name.clone()),
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        mods_dir: Some(mods_dir.to_string_lossy().to_string()),
+        ..Default::default()
+    });
+
+    Ok(format!("Mod {} v{} baixado com sucesso!", mod_name, version))
+}
+
+async fn fetch_url_to_file(http: &HttpService, url: &str, file_path: &PathBuf) -> Result<(), String> {
+    let response = http.send(http.client().get(url))
         .await
         .map_err(|e| format!("Erro no download: {}", e))?;
 
@@ -1687,26 +1924,22 @@ pub(crate) async fn download_mod_to_profile_internal(
     let bytes = response
         .bytes()
         .await
-        .map_err(|e| format!("Erro ao ler dados do download: {}", e))?;
+        .map_err(|e| format!("Erro ao ler dados do download: {}", e.without_url()))?;
 
-    fs::write(&file_path, bytes)
-        .map_err(|e| format!("Erro ao salvar arquivo: {}", e))?;
-
-    // Atualizar arquivos JSON de controle
-    add_mod_to_lists(&profile_dir, &mod_name, &version, &file_name, factorio_version)
-        .map_err(|e| format!("Erro ao atualizar listas de mods: {}", e))?;
-
-    Ok(format!("Mod {} v{} baixado com sucesso!", mod_name, version))
+    fs::write(file_path, bytes)
+        .map_err(|e| format!("Erro ao salvar arquivo: {}", e))
 }
 
 // Public command wrapper for direct calls
 #[tauri::command]
 async fn download_mod_to_profile(
+    app: tauri::AppHandle,
+    http: tauri::State<'_, HttpService>,
     mod_name: String,
     version: String,
     profile_name: String,
 ) -> Result<String, String> {
-    download_mod_to_profile_internal(mod_name, version, profile_name).await
+    download_mod_to_profile_internal(&app, &http, mod_name, version, profile_name).await
 }
 
 // Função para obter mods instalados de um perfil
@@ -1716,7 +1949,37 @@ async fn get_installed_mods(profile_name: String) -> Result<Vec<InternalModEntry
     let profile_dir = profiles_dir.join(&profile_name);
     
     // Carregar lista interna de mods
-    let internal_list = load_internal_mod_list(&profile_dir)?;
+    let mut internal_list = load_internal_mod_list(&profile_dir)?;
+    
+    // Entradas antigas não têm os metadados do info.json: completar a partir dos arquivos instalados
+    let mods_dir = resolve_profile_mods_dir(&profile_dir)?;
+    let language = mod_locale::user_language().await;
+    let mut enriched = false;
+    for entry in internal_list.mods.iter_mut() {
+        let before = entry.clone();
+        let path = mods_dir.join(&entry.file_name);
+        let kind = mod_install::install_kind(&path);
+        entry.kind = kind;
+        // Pastas e links de desenvolvimento mudam sem passar pelo app: relê sempre
+        if entry.title.is_none() || !kind.is_managed() {
+            if let Ok(info) = mod_archive::read_info_json_async(&path).await {
+                entry.apply_info(&info);
+            }
+        }
+        // Títulos traduzidos para a tela e a busca funcionarem offline
+        if entry.locale.as_deref() != Some(language.as_str()) {
+            if let Ok(names) = mod_locale::mod_names_async(&path, &entry.name, &language).await {
+                entry.localised_title = names.title;
+                entry.localised_description = names.description;
+                entry.locale = Some(language.clone());
+            }
+        }
+        // Relidos sem mudança (caso comum das pastas) não regravam a lista
+        enriched |= *entry != before;
+    }
+    if enriched {
+        save_internal_mod_list(&profile_dir, &internal_list)?;
+    }
     
     Ok(internal_list.mods)
 }
@@ -1724,6 +1987,7 @@ async fn get_installed_mods(profile_name: String) -> Result<Vec<InternalModEntry
 // Função para deletar um mod
 #[tauri::command]
 async fn delete_mod_file(
+    app: tauri::AppHandle,
     profile_name: String,
     mod_name: String,
     file_path: String
@@ -1735,9 +1999,9 @@ async fn delete_mod_file(
     // Construir caminho completo do arquivo
     let full_file_path = mods_dir.join(&file_path);
     
-    // Deletar arquivo físico
-    if full_file_path.exists() {
-        fs::remove_file(&full_file_path)
+    // Deletar zip, pasta ou link (de um symlink sai só o link; symlink quebrado também conta)
+    if fs::symlink_metadata(&full_file_path).is_ok() {
+        mod_install::remove_installed(&full_file_path)
             .map_err(|e| format!("Erro ao deletar arquivo: {}", e))?;
     }
     
@@ -1749,6 +2013,7 @@ async fn delete_mod_file(
     factorio_list.mods.retain(|m| m.name != mod_name);
     
     // Remover da lista interna
+    let removed_version = internal_list.mods.iter().find(|m| m.name == mod_name).map(|m| m.version.clone());
     internal_list.mods.retain(|m| m.name != mod_name);
     internal_list.last_updated = Utc::now().to_rfc3339();
     
@@ -1756,6 +2021,15 @@ async fn delete_mod_file(
     save_factorio_mod_list(&profile_dir, &factorio_list)?;
     save_internal_mod_list(&profile_dir, &internal_list)?;
     
+    hooks::fire(&app, hooks::HookEvent::ModRemoved, hooks::HookContext {
+        mod_name: Some(mod_name.clone()),
+        version: removed_version,
+        profile_name: Some(profile_name.clone()),
+        file_path: Some(full_file_path.to_string_lossy().to_string()),
+        mods_dir: Some(mods_dir.to_string_lossy().to_string()),
+        ..Default::default()
+    });
+    
     Ok(format!("Mod {} removido com sucesso!", mod_name))
 }
 
@@ -1773,9 +2047,13 @@ async fn toggle_mod_status(
     let mut factorio_list = load_factorio_mod_list(&profile_dir)?;
     let mut internal_list = load_internal_mod_list(&profile_dir)?;
     
-    // Atualizar na lista do Factorio
-    if let Some(factorio_mod) = factorio_list.mods.iter_mut().find(|m| m.name == mod_name) {
-        factorio_mod.enabled = enabled;
+    // Atualizar na lista do Factorio (mods em pasta/symlink podem não ter entrada ainda)
+    match factorio_list.mods.iter_mut().find(|m| m.name == mod_name) {
+        Some(factorio_mod) => factorio_mod.enabled = enabled,
+        None if internal_list.mods.iter().any(|m| m.name == mod_name) => {
+            factorio_list.mods.push(FactorioModListEntry { name: mod_name.clone(), enabled });
+        }
+        None => {}
     }
     
     // Atualizar na lista interna
@@ -1802,10 +2080,16 @@ async fn test_normalize(name: String) -> Result<String, String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Cliente HTTP compartilhado (também usado pela fila de downloads)
+    let http = HttpService::default();
     // Initialize download queue state
-    let queue_manager = download_queue::DownloadQueueManager::default();
+    let queue_manager = download_queue::DownloadQueueManager {
+        http: http.clone(),
+        ..Default::default()
+    };
 
     tauri::Builder::default()
+        .manage(http)
         .manage(queue_manager)
         .plugin(tauri_plugin_opener::init())
     .plugin(tauri_plugin_dialog::init())
@@ -1845,24 +2129,86 @@ pub fn run() {
             // Download queue commands
             download_queue::enqueue_download,
             download_queue::get_download_queue,
+            download_queue::get_download_queue_snapshot,
+            download_queue::get_queue_estimate,
             download_queue::pause_all_downloads,
             download_queue::resume_all_downloads,
             download_queue::pause_download,
             download_queue::resume_download,
             download_queue::remove_from_queue,
             download_queue::move_to_top,
+            download_queue::move_to_position,
+            download_queue::move_up,
+            download_queue::move_down,
+            download_queue::set_download_priority,
+            download_queue::sort_queue_by_priority,
+            download_queue::get_download_queue_grouped,
+            download_queue::pause_group,
+            download_queue::resume_group,
+            download_queue::cancel_group,
+            download_queue::set_download_rate_limit,
             download_queue::clear_download_queue,
-            download_queue::cancel_all_downloads
+            download_queue::cancel_all_downloads,
+            // Download history
+            download_history::get_download_history,
+            download_history::clear_download_history,
+            // Store compartilhado de mods
+            mod_store::get_mod_store_stats,
+            mod_store::gc_mod_store,
+            // Conciliação da pasta de mods com as listas
+            mod_reconcile::scan_profile_mods,
+            mod_reconcile::repair_profile_mods,
+            // Atualizações dos mods instalados
+            update_checker::check_mod_updates,
+            version_pin::set_mod_hold,
+            // Versões anteriores e rollback
+            version_archive::get_archived_versions,
+            version_archive::rollback_mod,
+            // mod-settings.dat do perfil
+            mod_settings::get_mod_settings,
+            mod_settings::set_mod_setting,
+            // Configurações declaradas pelos mods (settings.lua)
+            setting_definitions::get_mod_setting_definitions,
+            // changelog.txt entre a versão instalada e outra
+            changelog::get_mod_changelog
         ])
         .setup(|app| {
+            let app_cfg = tauri::async_runtime::block_on(load_config()).ok();
+            // Proxy/CA e endereços configurados valem desde a primeira requisição
+            if let Some(cfg) = app_cfg.as_ref() {
+                let http = app.state::<HttpService>();
+                if let Err(e) = http.apply_network_settings(&network_settings_from(cfg)) {
+                    eprintln!("Configuração de rede ignorada: {}", e);
+                }
+                if let Err(e) = http.set_endpoints(cfg.endpoints.clone()) {
+                    eprintln!("Endereços configurados ignorados: {}", e);
+                }
+            }
             // Load persisted queue and start if needed
             if let Some(mgr_state) = app.try_state::<download_queue::DownloadQueueManager>() {
                 mgr_state.load_persist();
+                if let Some(cfg) = app_cfg.as_ref() {
+                    mgr_state.apply_limits(queue_limits_from(cfg));
+                    mgr_state.history.set_retention_days(cfg.history_retention_days);
+                }
+                // Itens concluídos de versões anteriores da fila vão para o histórico
+                mgr_state.archive_finished(app.handle());
                 // Emit initial state
                 mgr_state.emit_update(&app.handle());
                 let mgr = mgr_state.inner().clone();
-                download_queue::DownloadQueueManager::start_next_if_idle_owned(mgr, app.handle().clone());
+                download_queue::DownloadQueueManager::start_next_if_idle_owned(mgr.clone(), app.handle().clone());
+
+                // Reavaliar periodicamente a fila para iniciar downloads quando uma janela de horário abrir
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    loop {
+                        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                        download_queue::DownloadQueueManager::start_next_if_idle_owned(mgr.clone(), handle.clone());
+                    }
+                });
             }
+            // Verificação periódica de atualizações (só roda se houver intervalo configurado)
+            update_checker::start_background_checks(app.handle().clone());
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -1871,10 +2217,3 @@ pub fn run() {
 
 // Public helpers for download_queue module
 pub(crate) fn get_profiles_dir_pub() -> Result<PathBuf, String> { get_profiles_dir() }
-
-pub(crate) async fn get_factorio_version_for(mod_name: String, version: String) -> Result<String, String> {
-    let details = fetch_mod_details(mod_name.clone()).await?;
-    let target = details.releases.iter().find(|r| r.version == version)
-        .ok_or_else(|| format!("Versão {} não encontrada para {}", version, mod_name))?;
-    Ok(target.factorio_version.clone())
-}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path};
+use serde::{Deserialize, Serialize};
+
+use crate::download_failure::{DownloadFailure, FailureKind};
+
+// Campos de info.json: conferidos após o download e guardados na lista interna
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveInfoJson {
+	pub name: String,
+	pub version: String,
+	#[serde(default)]
+	pub factorio_version: Option<String>,
+	#[serde(default)]
+	pub title: Option<String>,
+	#[serde(default)]
+	pub author: Option<String>,
+	#[serde(default)]
+	pub description: Option<String>,
+	// Formato do portal: "base >= 1.1", "? mod-opcional", "! incompatível"
+	#[serde(default)]
+	pub dependencies: Vec<String>,
+}
+
+impl ArchiveInfoJson {
+	// Sem factorio_version, o jogo assume 0.12
+	pub fn factorio_version_or_default(&self) -> String {
+		self.factorio_version.clone().unwrap_or_else(|| "0.12".into())
+	}
+}
+
+fn integrity(message: String) -> DownloadFailure {
+	DownloadFailure::new(FailureKind::Integrity, message)
+}
+
+// Caminho de entrada do zip seguro para extrair: relativo e sem ".."
+fn is_safe_entry_path(name: &str) -> bool {
+	if name.starts_with('/') || name.starts_with('\\') { return false; }
+	// "C:..." também é absoluto no Windows, mesmo lido em outra plataforma
+	if name.len() >= 2 && name.as_bytes()[1] == b':' { return false; }
+	Path::new(&name.replace('\\', "/"))
+		.components()
+		.all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+// Confere a estrutura do zip de um mod: uma única pasta raiz <name>_<version>/ (ou <name>/),
+// nenhum caminho absoluto ou com "..", e info.json com name/version iguais aos esperados
+pub fn validate_mod_zip(path: &Path, mod_name: &str, version: &str) -> Result<ArchiveInfoJson, DownloadFailure> {
+	let file = fs::File::open(path).map_err(DownloadFailure::disk)?;
+	let mut archive = zip::ZipArchive::new(file)
+		.map_err(|e| integrity(format!("Arquivo zip inválido: {}", e)))?;
+	if archive.is_empty() {
+		return Err(integrity("Arquivo zip vazio".into()));
+	}
+
+	let mut root: Option<String> = None;
+	for i in 0..archive.len() {
+		let entry = archive.by_index(i).map_err(|e| integrity(format!("Entrada do zip ilegível: {}", e)))?;
+		let name = entry.name().to_string();
+		if !is_safe_entry_path(&name) {
+			return Err(integrity(format!("Caminho inseguro no zip: {}", name)));
+		}
+		let first = name.replace('\\', "/").split('/').next().unwrap_or_default().to_string();
+		// Arquivo solto na raiz do zip (sem pasta)
+		if !name.contains('/') && !name.contains('\\') && !entry.is_dir() {
+			return Err(integrity(format!("Arquivo fora da pasta do mod: {}", name)));
+		}
+		match &root {
+			None => root = Some(first),
+			Some(r) if *r != first => {
+				return Err(integrity(format!("O zip deve ter uma única pasta raiz (encontradas {} e {})", r, first)));
+			}
+			_ => {}
+		}
+	}
+
+	let root = root.unwrap_or_default();
+	let expected_versioned = format!("{}_{}", mod_name, version);
+	if root != expected_versioned && root != mod_name {
+		return Err(integrity(format!("Pasta raiz inesperada: {} (esperado {} ou {})", root, expected_versioned, mod_name)));
+	}
+
+	let mut content = String::new();
+	archive
+		.by_name(&format!("{}/info.json", root))
+		.map_err(|_| integrity(format!("info.json não encontrado em {}/", root)))?
+		.read_to_string(&mut content)
+		.map_err(|e| integrity(format!("Erro ao ler info.json: {}", e)))?;
+	let info = parse_info_json(&content).map_err(integrity)?;
+
+	if info.name != mod_name {
+		return Err(integrity(format!("info.json declara o mod '{}', esperado '{}'", info.name, mod_name)));
+	}
+	if info.version != version {
+		return Err(integrity(format!("info.json declara a versão {}, esperada {}", info.version, version)));
+	}
+	Ok(info)
+}
+
+// Versão async: a leitura do zip roda fora do runtime
+pub async fn validate_mod_zip_async(path: &Path, mod_name: &str, version: &str) -> Result<ArchiveInfoJson, DownloadFailure> {
+	let (path, mod_name, version) = (path.to_path_buf(), mod_name.to_string(), version.to_string());
+	tokio::task::spawn_blocking(move || validate_mod_zip(&path, &mod_name, &version))
+		.await
+		.map_err(DownloadFailure::other)?
+}
+
+fn parse_info_json(content: &str) -> Result<ArchiveInfoJson, String> {
+	serde_json::from_str(content.trim_start_matches('\u{feff}'))
+		.map_err(|e| format!("info.json inválido: {}", e))
+}
+
+// Lê info.json de um mod instalado sem saber de antemão nome/versão: zip (<raiz>/info.json) ou pasta
+pub fn read_info_json(path: &Path) -> Result<ArchiveInfoJson, String> {
+	if path.is_dir() {
+		let content = fs::read_to_string(path.join("info.json"))
+			.map_err(|e| format!("Erro ao ler info.json de {}: {}", path.display(), e))?;
+		return parse_info_json(&content);
+	}
+	let file = fs::File::open(path).map_err(|e| format!("Erro ao abrir {}: {}", path.display(), e))?;
+	let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Arquivo zip inválido: {}", e))?;
+	let info_name = archive
+		.file_names()
+		.find(|n| n.ends_with("/info.json") && n.matches('/').count() == 1)
+		.map(String::from)
+		.ok_or_else(|| format!("info.json não encontrado em {}", path.display()))?;
+	let mut content = String::new();
+	archive
+		.by_name(&info_name)
+		.map_err(|e| format!("Erro ao ler info.json: {}", e))?
+		.read_to_string(&mut content)
+		.map_err(|e| format!("Erro ao ler info.json: {}", e))?;
+	parse_info_json(&content)
+}
+
+pub async fn read_info_json_async(path: &Path) -> Result<ArchiveInfoJson, String> {
+	let path = path.to_path_buf();
+	tokio::task::spawn_blocking(move || read_info_json(&path))
+		.await
+		.map_err(|e| format!("Erro ao ler info.json: {}", e))?
+}
+
+// Arquivos maiores que isso não são lidos por read_mod_files (lua/txt/cfg de mods são pequenos)
+const MAX_TEXT_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+fn collect_dir_files(root: &Path, dir: &Path, filter: &dyn Fn(&str) -> bool, out: &mut BTreeMap<String, String>) {
+	let Ok(entries) = fs::read_dir(dir) else { return; };
+	for entry in entries.flatten() {
+		let path = entry.path();
+		// symlink_metadata: não seguir links para fora da pasta do mod
+		let Ok(meta) = fs::symlink_metadata(&path) else { continue; };
+		if meta.is_dir() {
+			collect_dir_files(root, &path, filter, out);
+			continue;
+		}
+		let Ok(relative) = path.strip_prefix(root) else { continue; };
+		let relative = relative.to_string_lossy().replace('\\', "/");
+		if meta.is_file() && meta.len() <= MAX_TEXT_FILE_SIZE && filter(&relative) {
+			if let Ok(bytes) = fs::read(&path) {
+				out.insert(relative, String::from_utf8_lossy(&bytes).into_owned());
+			}
+		}
+	}
+}
+
+// Arquivos de texto de um mod instalado (zip ou pasta) cujo caminho relativo à raiz do mod
+// passa no filtro, ex.: "settings.lua", "locale/pt-BR/mod.cfg". Chave = caminho com "/"
+pub fn read_mod_files(path: &Path, filter: impl Fn(&str) -> bool) -> Result<BTreeMap<String, String>, String> {
+	let mut files = BTreeMap::new();
+	if path.is_dir() {
+		collect_dir_files(path, path, &filter, &mut files);
+		return Ok(files);
+	}
+	let file = fs::File::open(path).map_err(|e| format!("Erro ao abrir {}: {}", path.display(), e))?;
+	let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Arquivo zip inválido: {}", e))?;
+	for i in 0..archive.len() {
+		let mut entry = archive.by_index(i).map_err(|e| format!("Entrada do zip ilegível: {}", e))?;
+		let name = entry.name().replace('\\', "/");
+		if entry.is_dir() || !is_safe_entry_path(&name) || entry.size() > MAX_TEXT_FILE_SIZE {
+			continue;
+		}
+		// Tudo fica dentro da pasta raiz <mod>_<versão>/
+		let Some((_, relative)) = name.split_once('/') else { continue; };
+		if !filter(relative) {
+			continue;
+		}
+		let mut bytes = Vec::new();
+		entry.read_to_end(&mut bytes).map_err(|e| format!("Erro ao ler {}: {}", name, e))?;
+		files.insert(relative.to_string(), String::from_utf8_lossy(&bytes).into_owned());
+	}
+	Ok(files)
+}
@@ -0,0 +1,67 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::mod_store;
+
+// Forma como o mod está na pasta do perfil. Além do zip, o Factorio carrega pastas
+// <name> ou <name>_<version>, e autores costumam apontar um symlink para a cópia de trabalho
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallKind {
+	#[default]
+	Zip,
+	Folder,
+	Symlink,
+}
+
+impl InstallKind {
+	// Pastas e links são mantidos pelo usuário: limpezas automáticas nunca os apagam
+	pub fn is_managed(self) -> bool {
+		self == InstallKind::Zip
+	}
+}
+
+// Não segue o link: um symlink para pasta é Symlink, não Folder.
+// No Windows, junções também são reportadas como symlink
+pub fn install_kind(path: &Path) -> InstallKind {
+	match fs::symlink_metadata(path) {
+		Ok(meta) if meta.file_type().is_symlink() => InstallKind::Symlink,
+		Ok(meta) if meta.is_dir() => InstallKind::Folder,
+		_ => InstallKind::Zip,
+	}
+}
+
+// file_name é exatamente <mod_name>_<versão>.zip (evita casar "mod_extra_1.0.0.zip" com "mod")
+pub fn is_versioned_zip_of(file_name: &str, mod_name: &str) -> bool {
+	file_name
+		.strip_prefix(mod_name)
+		.and_then(|rest| rest.strip_prefix('_'))
+		.and_then(|rest| rest.strip_suffix(".zip"))
+		.is_some_and(|version| !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+}
+
+// Remove um mod instalado conforme o tipo: de um symlink sai só o link, nunca o alvo
+pub fn remove_installed(path: &Path) -> std::io::Result<()> {
+	match install_kind(path) {
+		// Links de diretório/junções no Windows saem com remove_dir
+		InstallKind::Symlink => fs::remove_file(path).or_else(|_| fs::remove_dir(path)),
+		InstallKind::Folder => fs::remove_dir_all(path),
+		InstallKind::Zip => {
+			// O objeto no store só sai quando nenhum perfil o usa
+			mod_store::release(path);
+			fs::remove_file(path)
+		}
+	}
+}
+
+// Pasta ou symlink do mod (<name> ou <name>_<versão>) que um download de zip sobreporia
+pub fn find_unmanaged(mods_dir: &Path, mod_name: &str) -> Option<PathBuf> {
+	let prefix = format!("{}_", mod_name);
+	fs::read_dir(mods_dir).ok()?.flatten().map(|e| e.path()).find(|path| {
+		let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+		let versioned = name.strip_prefix(&prefix)
+			.is_some_and(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit() || c == '.'));
+		(name == mod_name || versioned || is_versioned_zip_of(&name, mod_name)) && !install_kind(path).is_managed()
+	})
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::OnceLock;
+use regex::Regex;
+
+use crate::mod_archive;
+
+// Seção -> chave -> texto. Chaves antes do primeiro [section] ficam na seção ""
+pub type LocaleSections = BTreeMap<String, BTreeMap<String, String>>;
+
+// Nome e descrição traduzidos de um mod (seções [mod-name] e [mod-description] do locale)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalisedNames {
+	pub title: Option<String>,
+	pub description: Option<String>,
+}
+
+// Formato dos locale/<idioma>/*.cfg: "[seção]", "chave=valor", comentários com ; ou #.
+// O valor vai até o fim da linha; "\n" literal vira quebra de linha e os parâmetros
+// (__1__, __ITEM__iron-plate__, ...) ficam como estão
+pub fn parse_cfg(text: &str, into: &mut LocaleSections) {
+	let mut section = String::new();
+	for line in text.trim_start_matches('\u{feff}').lines() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+			continue;
+		}
+		if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+			section = name.trim().to_string();
+			continue;
+		}
+		let Some((key, value)) = line.split_once('=') else { continue; };
+		// Chave repetida: vale a última, como no jogo
+		into.entry(section.clone())
+			.or_default()
+			.insert(key.trim().to_string(), value.trim().replace("\\n", "\n"));
+	}
+}
+
+// Texto com parâmetros que só o jogo resolve (referências a itens, teclas, __1__ sem argumento)
+pub fn has_placeholders(text: &str) -> bool {
+	static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+	PLACEHOLDER.get_or_init(|| Regex::new(r"__[A-Za-z0-9]+__").unwrap()).is_match(text)
+}
+
+// "pt_BR.UTF-8" (LANG do sistema) ou "pt_BR" -> "pt-BR", o nome das pastas de locale do Factorio
+fn normalize_language(raw: &str) -> Option<String> {
+	let code = raw.split(['.', '@']).next().unwrap_or_default().trim().replace('_', "-");
+	if code.is_empty() || code == "C" || code == "POSIX" {
+		return None;
+	}
+	Some(code)
+}
+
+// locale= da seção [general] do config.ini do Factorio; "auto" (padrão) segue o sistema
+fn factorio_locale() -> Option<String> {
+	let path = crate::get_factorio_data_dir().ok()?.join("config").join("config.ini");
+	let text = std::fs::read_to_string(path).ok()?;
+	let mut sections = LocaleSections::new();
+	parse_cfg(&text, &mut sections);
+	sections.get("general")?.get("locale").filter(|l| !l.eq_ignore_ascii_case("auto")).cloned()
+}
+
+// Windows não define LANG: idioma do usuário pelo registro ("pt-BR"). Lido uma vez por execução
+#[cfg(windows)]
+fn system_locale() -> Option<String> {
+	static LOCALE: OnceLock<Option<String>> = OnceLock::new();
+	LOCALE.get_or_init(|| {
+		let output = std::process::Command::new("reg")
+			.args(["query", r"HKCU\Control Panel\International", "/v", "LocaleName"])
+			.output()
+			.ok()?;
+		String::from_utf8_lossy(&output.stdout).lines().find_map(|line| {
+			let value = line.trim().strip_prefix("LocaleName")?.trim().strip_prefix("REG_SZ")?;
+			Some(value.trim().to_string())
+		})
+	}).clone()
+}
+
+#[cfg(not(windows))]
+fn system_locale() -> Option<String> {
+	None
+}
+
+// Idioma para nomes de mods: AppConfig.locale, o locale do jogo no config.ini e, sem eles,
+// o do sistema (LC_ALL/LC_MESSAGES/LANG ou, no Windows, o registro); padrão "en"
+pub async fn user_language() -> String {
+	let configured = crate::load_config().await.ok().and_then(|cfg| cfg.locale);
+	let detected = tokio::task::spawn_blocking(|| {
+		factorio_locale()
+			.into_iter()
+			.chain(["LC_ALL", "LC_MESSAGES", "LANG"].iter().filter_map(|var| std::env::var(var).ok()))
+			.chain(std::iter::once_with(system_locale).flatten())
+			.find_map(|raw| normalize_language(&raw))
+	})
+	.await
+	.ok()
+	.flatten();
+	configured
+		.and_then(|raw| normalize_language(&raw))
+		.or(detected)
+		.unwrap_or_else(|| "en".into())
+}
+
+// Ordem de busca: idioma exato, só o idioma ("pt"), outra variante ("pt-PT"), inglês
+fn fallback_order(language: &str, available: &[&String]) -> Vec<String> {
+	let base = language.split('-').next().unwrap_or(language).to_lowercase();
+	let mut order: Vec<String> = Vec::new();
+	let candidates = available.iter().filter(|l| l.eq_ignore_ascii_case(language))
+		.chain(available.iter().filter(|l| l.eq_ignore_ascii_case(&base)))
+		.chain(available.iter().filter(|l| l.to_lowercase().starts_with(&format!("{}-", base))))
+		.chain(available.iter().filter(|l| l.as_str() == "en"));
+	for lang in candidates {
+		if !order.contains(*lang) {
+			order.push((*lang).clone());
+		}
+	}
+	order
+}
+
+// Locale de um mod instalado (zip ou pasta), agrupado por idioma; arquivos lidos em ordem alfabética
+pub fn read_locales(path: &Path) -> Result<BTreeMap<String, LocaleSections>, String> {
+	let files = mod_archive::read_mod_files(path, |p| {
+		p.starts_with("locale/") && p.ends_with(".cfg") && p.matches('/').count() == 2
+	})?;
+	let mut locales: BTreeMap<String, LocaleSections> = BTreeMap::new();
+	for (file, text) in files {
+		let language = file.split('/').nth(1).unwrap_or_default().to_string();
+		parse_cfg(&text, locales.entry(language).or_default());
+	}
+	Ok(locales)
+}
+
+// Cada campo cai para o próximo idioma da ordem quando falta, como o jogo faz com o inglês
+pub fn mod_names(path: &Path, mod_name: &str, language: &str) -> Result<LocalisedNames, String> {
+	let locales = read_locales(path)?;
+	let available: Vec<&String> = locales.keys().collect();
+	let lookup = |section: &str, usable: &dyn Fn(&str) -> bool| {
+		fallback_order(language, &available).iter().find_map(|lang| {
+			locales.get(lang)?.get(section)?.get(mod_name).filter(|t| !t.trim().is_empty() && usable(t)).cloned()
+		})
+	};
+	Ok(LocalisedNames {
+		// Nome com parâmetros não resolvidos ficaria pior que o title do info.json
+		title: lookup("mod-name", &|t| !has_placeholders(t)),
+		description: lookup("mod-description", &|_| true),
+	})
+}
+
+pub async fn mod_names_async(path: &Path, mod_name: &str, language: &str) -> Result<LocalisedNames, String> {
+	let (path, mod_name, language) = (path.to_path_buf(), mod_name.to_string(), language.to_string());
+	tokio::task::spawn_blocking(move || mod_names(&path, &mod_name, &language))
+		.await
+		.map_err(|e| format!("Erro ao ler locale: {}", e))?
+}
@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::{FactorioModListEntry, InternalModEntry};
+use crate::mod_archive::{self, ArchiveInfoJson};
+use crate::mod_install::{self, InstallKind};
+use crate::version_pin;
+use crate::mod_version::cmp_versions;
+
+// Mods que vêm com o jogo: aparecem no mod-list.json mas nunca na lista interna nem na pasta
+const BUILTIN_MODS: [&str; 4] = ["base", "elevated-rails", "quality", "space-age"];
+
+// Mod encontrado na pasta do perfil (zip, pasta descompactada ou symlink)
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskMod {
+	pub file_name: String,
+	pub kind: InstallKind,
+	#[serde(flatten)]
+	pub info: ArchiveInfoJson,
+}
+
+// Divergências entre a pasta de mods, internal-mod-list.json e mod-list.json
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReconcileReport {
+	pub profile_name: String,
+	pub mods_dir: String,
+	// Na pasta, mas sem entrada correspondente (mesmo file_name) na lista interna
+	pub untracked: Vec<DiskMod>,
+	// Entradas da lista interna cujo file_name não existe mais
+	pub missing_files: Vec<String>,
+	// Nomes presentes em só uma das listas
+	pub only_in_mod_list: Vec<String>,
+	pub only_in_internal: Vec<String>,
+	// Mods com enabled diferente nas duas listas (o jogo altera o mod-list.json)
+	pub enabled_mismatch: Vec<String>,
+	// Zips/pastas sem info.json legível; ficam de fora do reparo
+	pub unreadable: Vec<String>,
+}
+
+impl ReconcileReport {
+	pub fn is_clean(&self) -> bool {
+		self.untracked.is_empty()
+			&& self.missing_files.is_empty()
+			&& self.only_in_mod_list.is_empty()
+			&& self.only_in_internal.is_empty()
+			&& self.enabled_mismatch.is_empty()
+	}
+}
+
+// Lê os mods da pasta: zips e pastas com info.json; o restante (listas, mod-settings.dat) é ignorado.
+// Cada info.json é lido fora do runtime (zips grandes não travam os comandos async)
+async fn scan_mods_dir(mods_dir: &Path) -> (Vec<DiskMod>, Vec<String>) {
+	let mut found = Vec::new();
+	let mut unreadable = Vec::new();
+	let Ok(entries) = fs::read_dir(mods_dir) else { return (found, unreadable); };
+	for entry in entries.flatten() {
+		let path = entry.path();
+		let file_name = entry.file_name().to_string_lossy().to_string();
+		let is_folder = path.is_dir();
+		if !is_folder && !file_name.to_lowercase().ends_with(".zip") {
+			continue;
+		}
+		if is_folder && !path.join("info.json").is_file() {
+			continue;
+		}
+		match mod_archive::read_info_json_async(&path).await {
+			Ok(info) => found.push(DiskMod { file_name, kind: mod_install::install_kind(&path), info }),
+			Err(e) => {
+				println!("Mod ilegível em {}: {}", path.display(), e);
+				unreadable.push(file_name);
+			}
+		}
+	}
+	found.sort_by(|a, b| a.info.name.cmp(&b.info.name).then(cmp_versions(&a.info.version, &b.info.version)));
+	(found, unreadable)
+}
+
+async fn build_report(profile_name: &str, profile_dir: &PathBuf) -> Result<ReconcileReport, String> {
+	let mods_dir = crate::resolve_profile_mods_dir(profile_dir)?;
+	let factorio_list = crate::load_factorio_mod_list(profile_dir)?;
+	let internal_list = crate::load_internal_mod_list(profile_dir)?;
+	let (disk_mods, unreadable) = scan_mods_dir(&mods_dir).await;
+
+	let tracked_files: HashSet<&str> = internal_list.mods.iter().map(|m| m.file_name.as_str()).collect();
+	let internal_names: HashSet<&str> = internal_list.mods.iter().map(|m| m.name.as_str()).collect();
+	let factorio_names: HashSet<&str> = factorio_list.mods.iter().map(|m| m.name.as_str()).collect();
+
+	let mut report = ReconcileReport {
+		profile_name: profile_name.to_string(),
+		mods_dir: mods_dir.to_string_lossy().to_string(),
+		unreadable,
+		..Default::default()
+	};
+	report.untracked = disk_mods.into_iter().filter(|d| !tracked_files.contains(d.file_name.as_str())).collect();
+	report.missing_files = internal_list.mods.iter()
+		.filter(|m| !mods_dir.join(&m.file_name).exists())
+		.map(|m| m.name.clone())
+		.collect();
+	report.only_in_mod_list = factorio_list.mods.iter()
+		.filter(|m| !BUILTIN_MODS.contains(&m.name.as_str()) && !internal_names.contains(m.name.as_str()))
+		.map(|m| m.name.clone())
+		.collect();
+	report.only_in_internal = internal_list.mods.iter()
+		.filter(|m| !factorio_names.contains(m.name.as_str()))
+		.map(|m| m.name.clone())
+		.collect();
+	report.enabled_mismatch = internal_list.mods.iter()
+		.filter(|m| factorio_list.mods.iter().any(|f| f.name == m.name && f.enabled != m.enabled))
+		.map(|m| m.name.clone())
+		.collect();
+	Ok(report)
+}
+
+// Compara a pasta de mods do perfil com as duas listas, sem alterar nada
+#[tauri::command]
+pub async fn scan_profile_mods(profile_name: String) -> Result<ReconcileReport, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	build_report(&profile_name, &profile_dir).await
+}
+
+// Aplica o reparo: importa o que foi colocado à mão, descarta entradas sem arquivo,
+// alinha as duas listas e adota o enabled do mod-list.json (alterado pelo jogo)
+#[tauri::command]
+pub async fn repair_profile_mods(profile_name: String) -> Result<ReconcileReport, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	let report = build_report(&profile_name, &profile_dir).await?;
+	if report.is_clean() {
+		return Ok(report);
+	}
+	let mods_dir = PathBuf::from(&report.mods_dir);
+	let mut factorio_list = crate::load_factorio_mod_list(&profile_dir)?;
+	let mut internal_list = crate::load_internal_mod_list(&profile_dir)?;
+
+	internal_list.mods.retain(|m| mods_dir.join(&m.file_name).exists());
+
+	// Se houver mais de uma versão do mesmo mod na pasta, vale a maior (untracked vem ordenado).
+	// Mod fixado (ou com restrição que a versão não atende) continua na versão registrada;
+	// o arquivo extra segue aparecendo no relatório
+	for disk in report.untracked.iter() {
+		let info = &disk.info;
+		let current = internal_list.mods.iter().find(|m| m.name == info.name);
+		if let Some(current) = current {
+			let held = current.pinned
+				|| current.version_constraint.as_deref().is_some_and(|c| !version_pin::satisfies(c, &info.version));
+			if held || cmp_versions(&current.version, &info.version) == std::cmp::Ordering::Greater {
+				continue;
+			}
+		}
+		let enabled = factorio_list.mods.iter().find(|f| f.name == info.name).map(|f| f.enabled).unwrap_or(true);
+		let mut entry = InternalModEntry::from_info(info, &disk.file_name, enabled);
+		entry.kind = disk.kind;
+		if let Some(current) = current {
+			entry.pinned = current.pinned;
+			entry.version_constraint = current.version_constraint.clone();
+		}
+		internal_list.mods.retain(|m| m.name != info.name);
+		internal_list.mods.push(entry);
+	}
+
+	// Entradas do mod-list.json sem mod instalado saem; mods instalados sem entrada entram
+	factorio_list.mods.retain(|f| BUILTIN_MODS.contains(&f.name.as_str()) || internal_list.mods.iter().any(|m| m.name == f.name));
+	for entry in internal_list.mods.iter_mut() {
+		match factorio_list.mods.iter().find(|f| f.name == entry.name) {
+			Some(f) => entry.enabled = f.enabled,
+			None => factorio_list.mods.push(FactorioModListEntry { name: entry.name.clone(), enabled: entry.enabled }),
+		}
+	}
+	internal_list.last_updated = Utc::now().to_rfc3339();
+
+	crate::save_factorio_mod_list(&profile_dir, &factorio_list)?;
+	crate::save_internal_mod_list(&profile_dir, &internal_list)?;
+	build_report(&profile_name, &profile_dir).await
+}
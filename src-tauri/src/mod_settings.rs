@@ -0,0 +1,384 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+
+use crate::property_tree::{self, PropertyTree, PropertyValue, Reader};
+
+// Seções de mod-settings.dat, na ordem em que o jogo as grava
+pub const SCOPES: [&str; 3] = ["startup", "runtime-global", "runtime-per-user"];
+
+// mod-settings.dat: versão do jogo que gravou o arquivo (4 x u16), um byte reservado (sempre 0)
+// e a property tree { <scope>: { <setting>: { "value": <valor> } } }
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModSettingsFile {
+	pub version: [u16; 4],
+	pub reserved: u8,
+	pub tree: PropertyTree,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModSettingEntry {
+	pub scope: String,
+	pub name: String,
+	pub value: serde_json::Value,
+	// bool, number, string, color (dicionário r/g/b/a), signed_integer, ...
+	pub value_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModSettingsView {
+	// None = o perfil ainda não tem mod-settings.dat (o jogo cria na primeira execução)
+	pub game_version: Option<String>,
+	pub settings: Vec<ModSettingEntry>,
+}
+
+impl ModSettingsFile {
+	pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+		let mut reader = Reader::new(bytes);
+		let mut version = [0u16; 4];
+		for part in version.iter_mut() {
+			*part = reader.read_u16()?;
+		}
+		let reserved = reader.read_u8()?;
+		let tree = reader.read_tree()?;
+		if !reader.is_at_end() {
+			return Err("Dados extras após a property tree em mod-settings.dat".into());
+		}
+		Ok(Self { version, reserved, tree })
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut out = Vec::new();
+		for part in self.version {
+			property_tree::write_u16(&mut out, part);
+		}
+		out.push(self.reserved);
+		property_tree::write_tree(&mut out, &self.tree);
+		out
+	}
+
+	pub fn load(path: &Path) -> Result<Self, String> {
+		let bytes = fs::read(path).map_err(|e| format!("Erro ao ler mod-settings.dat: {}", e))?;
+		Self::parse(&bytes).map_err(|e| format!("mod-settings.dat inválido: {}", e))
+	}
+
+	// Grava em arquivo temporário e renomeia: o jogo nunca vê um arquivo pela metade
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		let tmp = path.with_extension("dat.tmp");
+		fs::write(&tmp, self.to_bytes()).map_err(|e| format!("Erro ao salvar mod-settings.dat: {}", e))?;
+		fs::rename(&tmp, path).map_err(|e| format!("Erro ao salvar mod-settings.dat: {}", e))
+	}
+
+	pub fn version_string(&self) -> String {
+		self.version.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(".")
+	}
+
+	pub fn setting(&self, scope: &str, name: &str) -> Option<&PropertyTree> {
+		self.tree.get(scope)?.get(name)?.get("value")
+	}
+
+	// Altera ou cria a configuração, criando a seção se preciso
+	pub fn set_setting(&mut self, scope: &str, name: &str, value: PropertyValue) -> Result<(), String> {
+		if self.tree.get(scope).is_none() {
+			self.tree.insert(scope, PropertyTree::new(PropertyValue::Dictionary(Vec::new())))?;
+		}
+		let section = self.tree.get_mut(scope).ok_or("Seção inexistente")?;
+		match section.get_mut(name) {
+			Some(setting) => setting.insert("value", PropertyTree::new(value)),
+			None => {
+				let mut setting = PropertyTree::new(PropertyValue::Dictionary(Vec::new()));
+				setting.insert("value", PropertyTree::new(value))?;
+				section.insert(name, setting)
+			}
+		}
+	}
+
+	pub fn entries(&self) -> Vec<ModSettingEntry> {
+		let mut entries = Vec::new();
+		for scope in SCOPES {
+			let Some(PropertyValue::Dictionary(settings)) = self.tree.get(scope).map(|t| &t.value) else { continue; };
+			for (name, setting) in settings {
+				let Some(value) = setting.get("value") else { continue; };
+				entries.push(ModSettingEntry {
+					scope: scope.to_string(),
+					name: name.clone().unwrap_or_default(),
+					value: to_json(value),
+					value_type: value_type(&value.value).to_string(),
+				});
+			}
+		}
+		entries
+	}
+}
+
+fn value_type(value: &PropertyValue) -> &'static str {
+	match value {
+		PropertyValue::None => "none",
+		PropertyValue::Bool(_) => "bool",
+		PropertyValue::Number(_) => "number",
+		PropertyValue::String(_) => "string",
+		PropertyValue::List(_) => "list",
+		// Único dicionário usado como valor de configuração
+		PropertyValue::Dictionary(_) => "color",
+		PropertyValue::SignedInteger(_) => "signed_integer",
+		PropertyValue::UnsignedInteger(_) => "unsigned_integer",
+	}
+}
+
+fn to_json(tree: &PropertyTree) -> serde_json::Value {
+	use serde_json::Value;
+	match &tree.value {
+		PropertyValue::None => Value::Null,
+		PropertyValue::Bool(b) => Value::Bool(*b),
+		PropertyValue::Number(n) => serde_json::Number::from_f64(*n).map(Value::Number).unwrap_or(Value::Null),
+		PropertyValue::String(s) => Value::String(s.clone().unwrap_or_default()),
+		PropertyValue::List(items) => Value::Array(items.iter().map(|(_, v)| to_json(v)).collect()),
+		PropertyValue::Dictionary(entries) => Value::Object(
+			entries.iter().map(|(k, v)| (k.clone().unwrap_or_default(), to_json(v))).collect(),
+		),
+		PropertyValue::SignedInteger(i) => Value::from(*i),
+		PropertyValue::UnsignedInteger(u) => Value::from(*u),
+	}
+}
+
+// Converte o valor vindo da UI mantendo o tipo gravado (ex.: int-setting do 2.0 continua inteiro).
+// Sem valor gravado, `kind` (tipo da definição no settings.lua) decide: int-setting vira inteiro com sinal
+fn from_json(value: &serde_json::Value, existing: Option<&PropertyValue>, kind: Option<&str>) -> Result<PropertyValue, String> {
+	use serde_json::Value;
+	Ok(match (value, existing) {
+		(Value::Null, _) => PropertyValue::None,
+		(Value::Bool(b), None | Some(PropertyValue::Bool(_))) => PropertyValue::Bool(*b),
+		(Value::Number(n), None) if kind == Some("int-setting") => {
+			PropertyValue::SignedInteger(n.as_i64().ok_or_else(|| format!("Valor inteiro esperado: {}", n))?)
+		}
+		(Value::Number(n), Some(PropertyValue::SignedInteger(_))) => {
+			PropertyValue::SignedInteger(n.as_i64().ok_or_else(|| format!("Valor inteiro esperado: {}", n))?)
+		}
+		(Value::Number(n), Some(PropertyValue::UnsignedInteger(_))) => {
+			PropertyValue::UnsignedInteger(n.as_u64().ok_or_else(|| format!("Valor inteiro sem sinal esperado: {}", n))?)
+		}
+		(Value::Number(n), None | Some(PropertyValue::Number(_))) => {
+			PropertyValue::Number(n.as_f64().ok_or_else(|| format!("Número inválido: {}", n))?)
+		}
+		// String vazia continua marcada como vazia se já estava assim
+		(Value::String(s), Some(PropertyValue::String(None))) if s.is_empty() => PropertyValue::String(None),
+		(Value::String(s), None | Some(PropertyValue::String(_))) => PropertyValue::String(Some(s.clone())),
+		(Value::Object(map), None | Some(PropertyValue::Dictionary(_))) => {
+			let old: &[(Option<String>, PropertyTree)] = match existing { Some(PropertyValue::Dictionary(entries)) => entries, _ => &[] };
+			// Chaves já gravadas mantêm a ordem do arquivo (r, g, b, a); as novas vão no fim
+			let mut entries = Vec::new();
+			for (key, old_child) in old {
+				if let Some(child) = key.as_deref().and_then(|k| map.get(k)) {
+					entries.push((key.clone(), PropertyTree::new(from_json(child, Some(&old_child.value), None)?)));
+				}
+			}
+			for (key, child) in map.iter().filter(|(k, _)| !old.iter().any(|(o, _)| o.as_deref() == Some(k.as_str()))) {
+				entries.push((Some(key.clone()), PropertyTree::new(from_json(child, None, None)?)));
+			}
+			PropertyValue::Dictionary(entries)
+		}
+		(Value::Array(items), None | Some(PropertyValue::List(_))) => PropertyValue::List(
+			items.iter().map(|v| Ok((None, PropertyTree::new(from_json(v, None, None)?)))).collect::<Result<_, String>>()?,
+		),
+		(value, Some(existing)) => {
+			return Err(format!("Tipo incompatível: a configuração é {}, valor recebido {}", value_type(existing), value));
+		}
+	})
+}
+
+fn settings_path(profile_name: &str) -> Result<PathBuf, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(profile_name);
+	Ok(crate::resolve_profile_mods_dir(&profile_dir)?.join("mod-settings.dat"))
+}
+
+#[tauri::command]
+pub async fn get_mod_settings(profile_name: String) -> Result<ModSettingsView, String> {
+	let path = settings_path(&profile_name)?;
+	if !path.exists() {
+		return Ok(ModSettingsView::default());
+	}
+	let file = ModSettingsFile::load(&path)?;
+	Ok(ModSettingsView { game_version: Some(file.version_string()), settings: file.entries() })
+}
+
+#[tauri::command]
+pub async fn set_mod_setting(
+	profile_name: String,
+	scope: String,
+	name: String,
+	value: serde_json::Value,
+) -> Result<String, String> {
+	if !SCOPES.contains(&scope.as_str()) {
+		return Err(format!("Seção inválida: {} (use {})", scope, SCOPES.join(", ")));
+	}
+	let path = settings_path(&profile_name)?;
+	if !path.exists() {
+		return Err("mod-settings.dat ainda não existe; inicie o jogo uma vez com este perfil".into());
+	}
+	// Configuração declarada por um mod instalado: seção e valor precisam bater com o settings.lua
+	let definition = crate::setting_definitions::find_definition(&profile_name, &name).await?;
+	if let Some(definition) = definition.as_ref() {
+		if definition.setting_type != scope {
+			return Err(format!("{} pertence à seção {}, não a {}", name, definition.setting_type, scope));
+		}
+		definition.validate(&value)?;
+	}
+	let mut file = ModSettingsFile::load(&path)?;
+	// Inteiros com sinal só existem no formato 2.0; o 1.1 grava int-setting como número (double)
+	let kind = definition.as_ref().map(|d| d.kind.as_str()).filter(|_| file.version[0] >= 2);
+	let new_value = from_json(&value, file.setting(&scope, &name).map(|t| &t.value), kind)?;
+	file.set_setting(&scope, &name, new_value)?;
+	file.save(&path)?;
+	Ok(format!("Configuração {} atualizada", name))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn push_string(out: &mut Vec<u8>, s: &str) {
+		out.push(0);
+		if s.len() < 255 {
+			out.push(s.len() as u8);
+		} else {
+			out.push(255);
+			out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+		}
+		out.extend_from_slice(s.as_bytes());
+	}
+
+	fn push_dict_header(out: &mut Vec<u8>, count: u32) {
+		out.extend_from_slice(&[5, 0]);
+		out.extend_from_slice(&count.to_le_bytes());
+	}
+
+	// { "value": <valor> } como o jogo grava cada configuração
+	fn push_setting(out: &mut Vec<u8>, name: &str, value: &[u8]) {
+		push_string(out, name);
+		push_dict_header(out, 1);
+		push_string(out, "value");
+		out.extend_from_slice(value);
+	}
+
+	fn number(n: f64) -> Vec<u8> {
+		let mut v = vec![2, 0];
+		v.extend_from_slice(&n.to_bits().to_le_bytes());
+		v
+	}
+
+	// Arquivo montado à mão no formato gravado pelo Factorio 2.0
+	fn sample_file() -> Vec<u8> {
+		let mut out = Vec::new();
+		for part in [2u16, 0, 28, 0] {
+			out.extend_from_slice(&part.to_le_bytes());
+		}
+		out.push(0);
+		push_dict_header(&mut out, 3);
+
+		push_string(&mut out, "startup");
+		push_dict_header(&mut out, 3);
+		push_setting(&mut out, "my-mod-enable-thing", &[1, 0, 1]);
+		push_setting(&mut out, "my-mod-multiplier", &number(1.5));
+		let mut signed = vec![6, 0];
+		signed.extend_from_slice(&(-42i64).to_le_bytes());
+		push_setting(&mut out, "my-mod-offset", &signed);
+
+		push_string(&mut out, "runtime-global");
+		push_dict_header(&mut out, 2);
+		let mut long = vec![3, 0];
+		push_string(&mut long, &"x".repeat(300));
+		push_setting(&mut out, "my-mod-long-text", &long);
+		// String marcada como vazia pela flag
+		push_setting(&mut out, "my-mod-empty", &[3, 0, 1]);
+
+		push_string(&mut out, "runtime-per-user");
+		push_dict_header(&mut out, 1);
+		let mut color = Vec::new();
+		push_dict_header(&mut color, 4);
+		for (k, v) in [("r", 1.0), ("g", 0.5), ("b", 0.0), ("a", 1.0)] {
+			push_string(&mut color, k);
+			color.extend_from_slice(&number(v));
+		}
+		push_setting(&mut out, "my-mod-color", &color);
+		out
+	}
+
+	#[test]
+	fn round_trip_is_byte_for_byte() {
+		let bytes = sample_file();
+		let file = ModSettingsFile::parse(&bytes).unwrap();
+		assert_eq!(file.version, [2, 0, 28, 0]);
+		assert_eq!(file.to_bytes(), bytes);
+	}
+
+	#[test]
+	fn round_trip_keeps_any_type_flag_and_unsigned() {
+		let mut bytes = vec![1, 0, 1, 0, 110, 0, 0, 0, 0];
+		push_dict_header(&mut bytes, 1);
+		push_string(&mut bytes, "startup");
+		// any-type ligado e inteiro sem sinal
+		bytes.extend_from_slice(&[7, 1]);
+		bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+		let file = ModSettingsFile::parse(&bytes).unwrap();
+		assert_eq!(file.to_bytes(), bytes);
+	}
+
+	#[test]
+	fn reads_settings_as_json() {
+		let file = ModSettingsFile::parse(&sample_file()).unwrap();
+		let entries = file.entries();
+		assert_eq!(entries.len(), 6);
+		let offset = entries.iter().find(|e| e.name == "my-mod-offset").unwrap();
+		assert_eq!(offset.value, serde_json::json!(-42));
+		assert_eq!(offset.value_type, "signed_integer");
+		let color = entries.iter().find(|e| e.name == "my-mod-color").unwrap();
+		assert_eq!(color.scope, "runtime-per-user");
+		assert_eq!(color.value["g"], serde_json::json!(0.5));
+	}
+
+	#[test]
+	fn edit_keeps_type_and_other_bytes() {
+		let bytes = sample_file();
+		let mut file = ModSettingsFile::parse(&bytes).unwrap();
+		let existing = file.setting("startup", "my-mod-offset").map(|t| t.value.clone());
+		let value = from_json(&serde_json::json!(7), existing.as_ref(), None).unwrap();
+		assert_eq!(value, PropertyValue::SignedInteger(7));
+		file.set_setting("startup", "my-mod-offset", value).unwrap();
+
+		let edited = file.to_bytes();
+		assert_eq!(edited.len(), bytes.len());
+		let reparsed = ModSettingsFile::parse(&edited).unwrap();
+		assert_eq!(reparsed.setting("startup", "my-mod-offset").unwrap().value, PropertyValue::SignedInteger(7));
+
+		// Voltar ao valor original reproduz o arquivo original
+		file.set_setting("startup", "my-mod-offset", PropertyValue::SignedInteger(-42)).unwrap();
+		assert_eq!(file.to_bytes(), bytes);
+	}
+
+	#[test]
+	fn new_setting_is_added_to_scope() {
+		let mut file = ModSettingsFile::parse(&sample_file()).unwrap();
+		file.set_setting("runtime-global", "other-mod-flag", PropertyValue::Bool(true)).unwrap();
+		let reparsed = ModSettingsFile::parse(&file.to_bytes()).unwrap();
+		assert_eq!(reparsed.setting("runtime-global", "other-mod-flag").unwrap().value, PropertyValue::Bool(true));
+		assert!(from_json(&serde_json::json!("texto"), Some(&PropertyValue::Bool(false)), None).is_err());
+	}
+
+	#[test]
+	fn new_int_setting_uses_definition_kind() {
+		let value = from_json(&serde_json::json!(3), None, Some("int-setting")).unwrap();
+		assert_eq!(value, PropertyValue::SignedInteger(3));
+		assert!(from_json(&serde_json::json!(2.5), None, Some("int-setting")).is_err());
+		let value = from_json(&serde_json::json!(3), None, Some("double-setting")).unwrap();
+		assert_eq!(value, PropertyValue::Number(3.0));
+		// Valor já gravado continua mandando no tipo
+		let value = from_json(&serde_json::json!(3), Some(&PropertyValue::Number(1.0)), Some("int-setting")).unwrap();
+		assert_eq!(value, PropertyValue::Number(3.0));
+	}
+
+	#[test]
+	fn truncated_file_is_rejected() {
+		let bytes = sample_file();
+		assert!(ModSettingsFile::parse(&bytes[..bytes.len() - 3]).is_err());
+	}
+}
@@ -0,0 +1,248 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::download_failure::{DownloadFailure, FailureKind};
+use crate::download_queue::file_sha1_blocking;
+use crate::download_source::{DownloadSource, DownloadTarget};
+use crate::http_client::HttpService;
+
+// Store global de zips compartilhado entre perfis (ModManager/store).
+// Cada objeto é guardado uma vez por sha1; os perfis recebem hardlinks (ou cópias) e o índice
+// registra quais arquivos de perfil apontam para cada objeto.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreEntry {
+	pub name: String,
+	pub version: String,
+	pub sha1: String,
+	pub size: u64,
+	pub added_at: String,
+	// Caminhos dos arquivos de perfil que usam este objeto; vazio = pode ser apagado
+	#[serde(default)]
+	pub refs: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreIndex {
+	entries: Vec<StoreEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkKind {
+	Hardlink,
+	Copy,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StoreStats {
+	pub objects: usize,
+	pub total_bytes: u64,
+	pub references: usize,
+	// Bytes que cópias independentes por perfil ocupariam a mais
+	pub saved_bytes: u64,
+}
+
+fn store_dir() -> PathBuf {
+	dirs::data_dir()
+		.unwrap_or(std::env::temp_dir())
+		.join("ModManager")
+		.join("store")
+}
+
+fn index_path() -> PathBuf {
+	store_dir().join("index.json")
+}
+
+fn object_path(sha1: &str) -> PathBuf {
+	store_dir().join("objects").join(format!("{}.zip", sha1))
+}
+
+fn load_index() -> StoreIndex {
+	fs::read_to_string(index_path())
+		.ok()
+		.and_then(|c| serde_json::from_str::<StoreIndex>(&c).ok())
+		.unwrap_or_default()
+}
+
+fn save_index(index: &StoreIndex) -> Result<(), String> {
+	let _ = fs::create_dir_all(store_dir());
+	let json = serde_json::to_string_pretty(index)
+		.map_err(|e| format!("Erro ao serializar índice do store: {}", e))?;
+	fs::write(index_path(), json).map_err(|e| format!("Erro ao salvar índice do store: {}", e))
+}
+
+fn ref_key(path: &Path) -> String {
+	path.to_string_lossy().to_string()
+}
+
+// Coloca o objeto em dest: hardlink quando o sistema de arquivos permite, senão cópia
+pub fn link_object(object: &Path, dest: &Path) -> std::io::Result<LinkKind> {
+	if dest.exists() {
+		fs::remove_file(dest)?;
+	}
+	match fs::hard_link(object, dest) {
+		Ok(()) => Ok(LinkKind::Hardlink),
+		// Volumes diferentes (mods_path customizado em outro disco) ou FS sem suporte a hardlink
+		Err(_) => fs::copy(object, dest).map(|_| LinkKind::Copy),
+	}
+}
+
+// Remove a referência de todas as entradas; objetos sem referências são apagados
+fn release_key(index: &mut StoreIndex, key: &str, keep_sha1: Option<&str>) {
+	for entry in index.entries.iter_mut() {
+		entry.refs.retain(|r| r != key);
+	}
+	drop_orphans(index, keep_sha1);
+}
+
+fn drop_orphans(index: &mut StoreIndex, keep_sha1: Option<&str>) {
+	let (orphans, kept): (Vec<StoreEntry>, Vec<StoreEntry>) = std::mem::take(&mut index.entries)
+		.into_iter()
+		.partition(|e| e.refs.is_empty() && Some(e.sha1.as_str()) != keep_sha1);
+	index.entries = kept;
+	for orphan in orphans {
+		if !index.entries.iter().any(|e| e.sha1 == orphan.sha1) {
+			let _ = fs::remove_file(object_path(&orphan.sha1));
+		}
+	}
+}
+
+// Objeto já armazenado para <mod>/<versão>
+pub fn find(mod_name: &str, version: &str) -> Option<PathBuf> {
+	let _guard = STORE_LOCK.lock().unwrap();
+	load_index()
+		.entries
+		.iter()
+		.filter(|e| e.name == mod_name && e.version == version)
+		.map(|e| object_path(&e.sha1))
+		.find(|p| p.is_file())
+}
+
+// Registra no store o zip já colocado em path e passa a usar o objeto compartilhado no lugar dele
+pub fn adopt(path: &Path, mod_name: &str, version: &str, sha1: Option<&str>) -> Result<LinkKind, String> {
+	let sha1 = match sha1 {
+		Some(s) => s.to_string(),
+		None => file_sha1_blocking(path).ok_or_else(|| format!("Erro ao calcular SHA-1 de {}", path.display()))?,
+	};
+	let size = fs::metadata(path).map_err(|e| format!("Erro ao ler {}: {}", path.display(), e))?.len();
+	let key = ref_key(path);
+	let object = object_path(&sha1);
+
+	let _guard = STORE_LOCK.lock().unwrap();
+	let mut index = load_index();
+	// O arquivo pode ter substituído outro objeto (mesma versão, conteúdo diferente)
+	release_key(&mut index, &key, Some(&sha1));
+
+	let kind = if object.is_file() {
+		link_object(&object, path).map_err(|e| format!("Erro ao vincular {} ao store: {}", path.display(), e))?
+	} else {
+		if let Some(parent) = object.parent() {
+			fs::create_dir_all(parent).map_err(|e| format!("Erro ao criar diretório do store: {}", e))?;
+		}
+		match fs::hard_link(path, &object) {
+			Ok(()) => LinkKind::Hardlink,
+			Err(_) => {
+				fs::copy(path, &object).map_err(|e| format!("Erro ao copiar para o store: {}", e))?;
+				LinkKind::Copy
+			}
+		}
+	};
+
+	match index.entries.iter_mut().find(|e| e.sha1 == sha1 && e.name == mod_name && e.version == version) {
+		Some(entry) => {
+			if !entry.refs.contains(&key) { entry.refs.push(key); }
+		}
+		None => index.entries.push(StoreEntry {
+			name: mod_name.to_string(),
+			version: version.to_string(),
+			sha1,
+			size,
+			added_at: chrono::Utc::now().to_rfc3339(),
+			refs: vec![key],
+		}),
+	}
+	save_index(&index)?;
+	Ok(kind)
+}
+
+// O arquivo de perfil em path vai ser (ou foi) apagado: solta a referência
+pub fn release(path: &Path) {
+	let _guard = STORE_LOCK.lock().unwrap();
+	let mut index = load_index();
+	release_key(&mut index, &ref_key(path), None);
+	let _ = save_index(&index);
+}
+
+// Desfaz o vínculo antes de sobrescrever path: gravar sobre um hardlink alteraria o objeto compartilhado
+pub fn detach(path: &Path) {
+	if path.exists() {
+		release(path);
+		let _ = fs::remove_file(path);
+	}
+}
+
+// Solta todas as referências de arquivos dentro de dir (ex.: perfil apagado)
+pub fn release_under(dir: &Path) {
+	let _guard = STORE_LOCK.lock().unwrap();
+	let mut index = load_index();
+	let keys: Vec<String> = index.entries.iter()
+		.flat_map(|e| e.refs.iter())
+		.filter(|r| Path::new(r.as_str()).starts_with(dir))
+		.cloned()
+		.collect();
+	for key in keys.iter() {
+		release_key(&mut index, key, None);
+	}
+	let _ = save_index(&index);
+}
+
+// Fonte implícita consultada antes das configuradas: evita baixar de novo o que outro perfil já tem
+pub struct StoreSource;
+
+impl DownloadSource for StoreSource {
+	fn id(&self) -> String { "store".into() }
+
+	fn locate<'a>(&'a self, _http: &'a HttpService, mod_name: &'a str, version: &'a str) -> BoxFuture<'a, Result<DownloadTarget, DownloadFailure>> {
+		Box::pin(async move {
+			find(mod_name, version)
+				.map(DownloadTarget::Stored)
+				.ok_or_else(|| DownloadFailure::new(FailureKind::NotFound, format!("{} v{} não está no store", mod_name, version)))
+		})
+	}
+}
+
+#[tauri::command]
+pub fn get_mod_store_stats() -> Result<StoreStats, String> {
+	let _guard = STORE_LOCK.lock().unwrap();
+	let index = load_index();
+	Ok(StoreStats {
+		objects: index.entries.len(),
+		total_bytes: index.entries.iter().map(|e| e.size).sum(),
+		references: index.entries.iter().map(|e| e.refs.len()).sum(),
+		saved_bytes: index.entries.iter().map(|e| e.size * e.refs.len().saturating_sub(1) as u64).sum(),
+	})
+}
+
+// Remove referências a arquivos que não existem mais (apagados fora do app) e objetos órfãos
+#[tauri::command]
+pub fn gc_mod_store() -> Result<String, String> {
+	let _guard = STORE_LOCK.lock().unwrap();
+	let mut index = load_index();
+	let before = index.entries.len();
+	let stale: Vec<String> = index.entries.iter()
+		.flat_map(|e| e.refs.iter())
+		.filter(|r| !Path::new(r.as_str()).exists())
+		.cloned()
+		.collect();
+	for key in stale.iter() {
+		release_key(&mut index, key, None);
+	}
+	// Entradas sem referência (ex.: adoção interrompida) também saem
+	drop_orphans(&mut index, None);
+	save_index(&index)?;
+	Ok(format!("Store limpo: {} objetos removidos", before - index.entries.len()))
+}
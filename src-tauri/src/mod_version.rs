@@ -0,0 +1,15 @@
+use std::cmp::Ordering;
+
+// Versões de mods do Factorio ("1.2.10"): compara parte a parte como números;
+// partes ausentes ou não numéricas valem 0 ("1.1" == "1.1.0")
+pub fn cmp_versions(a: &str, b: &str) -> Ordering {
+	let pa: Vec<i64> = a.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
+	let pb: Vec<i64> = b.split('.').map(|s| s.parse::<i64>().unwrap_or(0)).collect();
+	let max_len = pa.len().max(pb.len());
+	for i in 0..max_len {
+		let ai = *pa.get(i).unwrap_or(&0);
+		let bi = *pb.get(i).unwrap_or(&0);
+		match ai.cmp(&bi) { Ordering::Equal => continue, ord => return ord }
+	}
+	Ordering::Equal
+}
@@ -0,0 +1,209 @@
+// Formato binário "property tree" do Factorio (mod-settings.dat, entre outros).
+// Cada nó: tipo (u8), flag any-type (u8) e os dados do tipo; inteiros e f64 em little-endian.
+// O leitor guarda tudo que o escritor precisa para reproduzir o arquivo byte a byte
+// (flag any-type, strings marcadas como vazias, ordem das chaves).
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyTree {
+	pub any_type: bool,
+	pub value: PropertyValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+	None,
+	Bool(bool),
+	Number(f64),
+	// None = string marcada como vazia pela flag (diferente de Some(""))
+	String(Option<String>),
+	List(Vec<(Option<String>, PropertyTree)>),
+	Dictionary(Vec<(Option<String>, PropertyTree)>),
+	// Tipos novos do 2.0
+	SignedInteger(i64),
+	UnsignedInteger(u64),
+}
+
+impl PropertyTree {
+	pub fn new(value: PropertyValue) -> Self {
+		Self { any_type: false, value }
+	}
+
+	// Filho de um dicionário pela chave
+	pub fn get(&self, key: &str) -> Option<&PropertyTree> {
+		match &self.value {
+			PropertyValue::Dictionary(entries) => entries.iter().find(|(k, _)| k.as_deref() == Some(key)).map(|(_, v)| v),
+			_ => None,
+		}
+	}
+
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut PropertyTree> {
+		match &mut self.value {
+			PropertyValue::Dictionary(entries) => entries.iter_mut().find(|(k, _)| k.as_deref() == Some(key)).map(|(_, v)| v),
+			_ => None,
+		}
+	}
+
+	// Insere ou substitui no dicionário, mantendo a posição da chave existente
+	pub fn insert(&mut self, key: &str, child: PropertyTree) -> Result<(), String> {
+		let PropertyValue::Dictionary(entries) = &mut self.value else {
+			return Err(format!("Nó não é um dicionário ao inserir '{}'", key));
+		};
+		match entries.iter_mut().find(|(k, _)| k.as_deref() == Some(key)) {
+			Some((_, v)) => *v = child,
+			None => entries.push((Some(key.to_string()), child)),
+		}
+		Ok(())
+	}
+}
+
+pub struct Reader<'a> {
+	data: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data, pos: 0 }
+	}
+
+	pub fn is_at_end(&self) -> bool {
+		self.pos >= self.data.len()
+	}
+
+	fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+		let end = self.pos.checked_add(n).filter(|e| *e <= self.data.len())
+			.ok_or_else(|| format!("Fim inesperado do arquivo na posição {}", self.pos))?;
+		let bytes = &self.data[self.pos..end];
+		self.pos = end;
+		Ok(bytes)
+	}
+
+	pub fn read_u8(&mut self) -> Result<u8, String> {
+		Ok(self.take(1)?[0])
+	}
+
+	pub fn read_u16(&mut self) -> Result<u16, String> {
+		Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+	}
+
+	pub fn read_u32(&mut self) -> Result<u32, String> {
+		Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+	}
+
+	fn read_u64(&mut self) -> Result<u64, String> {
+		Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+	}
+
+	fn read_bool(&mut self) -> Result<bool, String> {
+		match self.read_u8()? {
+			0 => Ok(false),
+			1 => Ok(true),
+			b => Err(format!("Valor booleano inválido {} na posição {}", b, self.pos - 1)),
+		}
+	}
+
+	// u8; 255 indica que o tamanho real vem em seguida como u32
+	fn read_space_optimized_u32(&mut self) -> Result<u32, String> {
+		match self.read_u8()? {
+			255 => self.read_u32(),
+			n => Ok(n as u32),
+		}
+	}
+
+	fn read_string(&mut self) -> Result<Option<String>, String> {
+		if self.read_bool()? {
+			return Ok(None);
+		}
+		let len = self.read_space_optimized_u32()? as usize;
+		let bytes = self.take(len)?;
+		String::from_utf8(bytes.to_vec()).map(Some).map_err(|e| format!("String inválida (UTF-8): {}", e))
+	}
+
+	fn read_entries(&mut self) -> Result<Vec<(Option<String>, PropertyTree)>, String> {
+		let count = self.read_u32()?;
+		// Não pré-alocar pelo count lido: arquivo corrompido poderia pedir gigabytes
+		let mut entries = Vec::new();
+		for _ in 0..count {
+			let key = self.read_string()?;
+			entries.push((key, self.read_tree()?));
+		}
+		Ok(entries)
+	}
+
+	pub fn read_tree(&mut self) -> Result<PropertyTree, String> {
+		let kind = self.read_u8()?;
+		let any_type = self.read_bool()?;
+		let value = match kind {
+			0 => PropertyValue::None,
+			1 => PropertyValue::Bool(self.read_bool()?),
+			2 => PropertyValue::Number(f64::from_bits(self.read_u64()?)),
+			3 => PropertyValue::String(self.read_string()?),
+			4 => PropertyValue::List(self.read_entries()?),
+			5 => PropertyValue::Dictionary(self.read_entries()?),
+			6 => PropertyValue::SignedInteger(self.read_u64()? as i64),
+			7 => PropertyValue::UnsignedInteger(self.read_u64()?),
+			other => return Err(format!("Tipo de property tree desconhecido: {}", other)),
+		};
+		Ok(PropertyTree { any_type, value })
+	}
+}
+
+pub fn write_u16(out: &mut Vec<u8>, v: u16) {
+	out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn write_u32(out: &mut Vec<u8>, v: u32) {
+	out.extend_from_slice(&v.to_le_bytes());
+}
+
+fn write_space_optimized_u32(out: &mut Vec<u8>, v: u32) {
+	if v < 255 {
+		out.push(v as u8);
+	} else {
+		out.push(255);
+		write_u32(out, v);
+	}
+}
+
+fn write_string(out: &mut Vec<u8>, s: &Option<String>) {
+	match s {
+		None => out.push(1),
+		Some(s) => {
+			out.push(0);
+			write_space_optimized_u32(out, s.len() as u32);
+			out.extend_from_slice(s.as_bytes());
+		}
+	}
+}
+
+fn write_entries(out: &mut Vec<u8>, entries: &[(Option<String>, PropertyTree)]) {
+	write_u32(out, entries.len() as u32);
+	for (key, child) in entries {
+		write_string(out, key);
+		write_tree(out, child);
+	}
+}
+
+pub fn write_tree(out: &mut Vec<u8>, tree: &PropertyTree) {
+	let kind = match tree.value {
+		PropertyValue::None => 0,
+		PropertyValue::Bool(_) => 1,
+		PropertyValue::Number(_) => 2,
+		PropertyValue::String(_) => 3,
+		PropertyValue::List(_) => 4,
+		PropertyValue::Dictionary(_) => 5,
+		PropertyValue::SignedInteger(_) => 6,
+		PropertyValue::UnsignedInteger(_) => 7,
+	};
+	out.push(kind);
+	out.push(tree.any_type as u8);
+	match &tree.value {
+		PropertyValue::None => {}
+		PropertyValue::Bool(b) => out.push(*b as u8),
+		PropertyValue::Number(n) => out.extend_from_slice(&n.to_bits().to_le_bytes()),
+		PropertyValue::String(s) => write_string(out, s),
+		PropertyValue::List(entries) | PropertyValue::Dictionary(entries) => write_entries(out, entries),
+		PropertyValue::SignedInteger(i) => out.extend_from_slice(&i.to_le_bytes()),
+		PropertyValue::UnsignedInteger(u) => out.extend_from_slice(&u.to_le_bytes()),
+	}
+}
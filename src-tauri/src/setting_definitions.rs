@@ -0,0 +1,399 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use mlua::{ChunkMode, HookTriggers, Lua, LuaOptions, StdLib, Table, Value as LuaValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{mod_archive, mod_install};
+
+// Estágios de configuração, na ordem em que o jogo os executa
+const STAGES: [&str; 3] = ["settings.lua", "settings-updates.lua", "settings-final-fixes.lua"];
+const SETTING_TYPES: [&str; 5] = ["bool-setting", "int-setting", "double-setting", "string-setting", "color-setting"];
+
+// Limites do Lua embutido: código de mod não confiável não pode travar nem esgotar o app
+const MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+const TIME_LIMIT: Duration = Duration::from_secs(5);
+const MAX_DEPTH: usize = 16;
+
+// Protótipo de configuração declarado pelo mod (campos do data:extend)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingDefinition {
+	pub name: String,
+	// bool-setting, int-setting, double-setting, string-setting ou color-setting
+	#[serde(rename = "type")]
+	pub kind: String,
+	// startup, runtime-global ou runtime-per-user (mesma seção de mod-settings.dat)
+	#[serde(default)]
+	pub setting_type: String,
+	#[serde(default)]
+	pub default_value: Value,
+	#[serde(default)]
+	pub minimum_value: Option<f64>,
+	#[serde(default)]
+	pub maximum_value: Option<f64>,
+	#[serde(default)]
+	pub allowed_values: Option<Vec<Value>>,
+	#[serde(default)]
+	pub allow_blank: bool,
+	#[serde(default)]
+	pub hidden: bool,
+	// Valor imposto quando a configuração está oculta
+	#[serde(default)]
+	pub forced_value: Option<Value>,
+	#[serde(default)]
+	pub order: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModSettingDefinitions {
+	pub mod_name: String,
+	pub version: String,
+	pub settings: Vec<SettingDefinition>,
+	// Erros dos estágios; ex.: settings-updates.lua mexendo em outro mod, que não existe aqui
+	pub warnings: Vec<String>,
+}
+
+impl SettingDefinition {
+	fn is_allowed(&self, value: &Value) -> bool {
+		let Some(allowed) = self.allowed_values.as_ref().filter(|a| !a.is_empty()) else { return true; };
+		allowed.iter().any(|a| a == value || (a.is_number() && a.as_f64() == value.as_f64()))
+	}
+
+	// Confere um valor vindo da UI antes de gravar em mod-settings.dat
+	pub fn validate(&self, value: &Value) -> Result<(), String> {
+		match self.kind.as_str() {
+			"bool-setting" if !value.is_boolean() => {
+				return Err(format!("{} espera verdadeiro/falso", self.name));
+			}
+			"int-setting" | "double-setting" => {
+				let n = value.as_f64().ok_or_else(|| format!("{} espera um número", self.name))?;
+				if self.kind == "int-setting" && n.fract() != 0.0 {
+					return Err(format!("{} espera um número inteiro", self.name));
+				}
+				if self.minimum_value.is_some_and(|min| n < min) || self.maximum_value.is_some_and(|max| n > max) {
+					return Err(format!(
+						"{} deve ficar entre {} e {}",
+						self.name,
+						self.minimum_value.map_or("-∞".into(), |v| v.to_string()),
+						self.maximum_value.map_or("∞".into(), |v| v.to_string())
+					));
+				}
+			}
+			"string-setting" => {
+				let s = value.as_str().ok_or_else(|| format!("{} espera um texto", self.name))?;
+				if s.is_empty() && !self.allow_blank {
+					return Err(format!("{} não pode ficar vazio", self.name));
+				}
+			}
+			"color-setting" => {
+				// {r, g, b, a} ou {1, 0, 0, 1}, como o jogo aceita
+				let channels: Vec<&Value> = match value {
+					Value::Object(map) => ["r", "g", "b"].iter().filter_map(|k| map.get(*k)).collect(),
+					Value::Array(items) => items.iter().take(3).collect(),
+					_ => Vec::new(),
+				};
+				if channels.len() != 3 || !channels.iter().all(|c| c.is_number()) {
+					return Err(format!("{} espera uma cor (r, g, b)", self.name));
+				}
+			}
+			_ => {}
+		}
+		if !self.is_allowed(value) {
+			return Err(format!("Valor não permitido para {}: {}", self.name, value));
+		}
+		Ok(())
+	}
+}
+
+// require() do Factorio: "prototypes.foo", "prototypes/foo.lua" ou "__<mod>__/foo";
+// relativo à pasta do arquivo que chamou e, depois, à raiz do mod
+const PRELUDE: &str = r#"
+local raw_load = load
+-- Só código-fonte: bytecode pré-compilado pode corromper a VM
+load = function(chunk, name, mode, env) return raw_load(chunk, name, "t", env) end
+dofile, loadfile, print = nil, nil, function() end
+log = function() end
+if string then string.dump = nil end
+
+-- Estouro do tempo limite não pode ser engolido por pcall dentro de um laço infinito
+local raw_pcall, raw_xpcall = pcall, xpcall
+local function guard(ok, ...)
+	if not ok and __fmm_expired() then error((...), 0) end
+	return ok, ...
+end
+pcall = function(...) return guard(raw_pcall(...)) end
+xpcall = function(...) return guard(raw_xpcall(...)) end
+
+function table_size(t)
+	local n = 0
+	for _ in pairs(t) do n = n + 1 end
+	return n
+end
+
+data = { raw = {} }
+function data:extend(prototypes)
+	for _, p in pairs(prototypes) do
+		if type(p) == "table" and type(p.type) == "string" and type(p.name) == "string" then
+			self.raw[p.type] = self.raw[p.type] or {}
+			self.raw[p.type][p.name] = p
+		end
+	end
+end
+
+local loaded, dirs = {}, { "" }
+function require(name)
+	local path, source = __fmm_source(tostring(name), dirs[#dirs])
+	if not path then error("module '" .. tostring(name) .. "' not found", 2) end
+	if loaded[path] ~= nil then return loaded[path] end
+	local chunk = assert(raw_load(source, "@" .. path, "t"))
+	dirs[#dirs + 1] = path:match("^(.*/)") or ""
+	local ok, result = pcall(chunk)
+	dirs[#dirs] = nil
+	if not ok then error(result, 0) end
+	if result == nil then result = true end
+	loaded[path] = result
+	return result
+end
+"#;
+
+fn module_path(mod_name: &str, name: &str) -> String {
+	let mut path = name.replace('\\', "/");
+	if let Some(rest) = path.strip_prefix(&format!("__{}__/", mod_name)) {
+		path = rest.to_string();
+	}
+	if !path.ends_with(".lua") {
+		path = format!("{}.lua", path.replace('.', "/"));
+	}
+	path.trim_start_matches("./").to_string()
+}
+
+fn lua_to_json(value: &LuaValue, depth: usize) -> Value {
+	match value {
+		LuaValue::Boolean(b) => Value::Bool(*b),
+		LuaValue::Integer(i) => Value::from(*i),
+		// Lua 5.2 só tem double: inteiros exatos viram inteiros no JSON
+		LuaValue::Number(n) if n.fract() == 0.0 && n.abs() < 9.0e15 => Value::from(*n as i64),
+		LuaValue::Number(n) => serde_json::Number::from_f64(*n).map(Value::Number).unwrap_or(Value::Null),
+		LuaValue::String(s) => Value::String(s.to_string_lossy().into_owned()),
+		LuaValue::Table(t) if depth < MAX_DEPTH => table_to_json(t, depth + 1),
+		_ => Value::Null,
+	}
+}
+
+fn table_to_json(table: &Table, depth: usize) -> Value {
+	let entries: Vec<(LuaValue, LuaValue)> = table.clone().pairs::<LuaValue, LuaValue>().flatten().collect();
+	// Sequência 1..n vira array; tabela vazia também (allowed_values = {})
+	if entries.len() == table.raw_len() {
+		let mut items: Vec<(i64, Value)> = entries
+			.iter()
+			.filter_map(|(k, v)| match k {
+				LuaValue::Integer(i) => Some((*i, lua_to_json(v, depth))),
+				LuaValue::Number(n) => Some((*n as i64, lua_to_json(v, depth))),
+				_ => None,
+			})
+			.collect();
+		if items.len() == entries.len() {
+			items.sort_by_key(|(i, _)| *i);
+			return Value::Array(items.into_iter().map(|(_, v)| v).collect());
+		}
+	}
+	let mut map = serde_json::Map::new();
+	for (key, value) in &entries {
+		let key = match key {
+			LuaValue::String(s) => s.to_string_lossy().into_owned(),
+			LuaValue::Integer(i) => i.to_string(),
+			LuaValue::Number(n) => n.to_string(),
+			_ => continue,
+		};
+		map.insert(key, lua_to_json(value, depth));
+	}
+	Value::Object(map)
+}
+
+fn sandbox(mod_name: &str, sources: BTreeMap<String, String>, mods: &[(String, String)]) -> mlua::Result<Lua> {
+	let lua = Lua::new_with(StdLib::TABLE | StdLib::STRING | StdLib::MATH | StdLib::BIT, LuaOptions::default())?;
+	lua.set_memory_limit(MEMORY_LIMIT)?;
+	let started = Instant::now();
+	lua.set_hook(HookTriggers::new().every_nth_instruction(10_000), move |_, _| {
+		if started.elapsed() > TIME_LIMIT {
+			return Err(mlua::Error::RuntimeError("tempo limite de execução excedido".into()));
+		}
+		Ok(())
+	});
+
+	{
+		let globals = lua.globals();
+		let owner = mod_name.to_string();
+		let source = lua.create_function(move |_, (name, dir): (String, String)| {
+			let path = module_path(&owner, &name);
+			let found = [format!("{}{}", dir, path), path]
+				.into_iter()
+				.find_map(|p| sources.get(&p).map(|src| (p, src.clone())));
+			Ok(found.map_or((None, None), |(p, src)| (Some(p), Some(src))))
+		})?;
+		globals.set("__fmm_source", source)?;
+		globals.set("__fmm_expired", lua.create_function(move |_, ()| Ok(started.elapsed() > TIME_LIMIT))?)?;
+		// mods[nome] = versão, como no estágio de configurações do jogo
+		let mods_table = lua.create_table()?;
+		for (name, version) in mods {
+			mods_table.set(name.as_str(), version.as_str())?;
+		}
+		globals.set("mods", mods_table)?;
+		globals.set("feature_flags", lua.create_table()?)?;
+	}
+	lua.load(PRELUDE).set_name("=prelude").exec()?;
+	Ok(lua)
+}
+
+// Roda os estágios de configuração de um mod (só ele e "base" em `mods`) e coleta os protótipos
+pub fn evaluate(info: &mod_archive::ArchiveInfoJson, sources: BTreeMap<String, String>) -> ModSettingDefinitions {
+	let mut result = ModSettingDefinitions {
+		mod_name: info.name.clone(),
+		version: info.version.clone(),
+		..Default::default()
+	};
+	let stages: Vec<(&str, String)> = STAGES.iter().filter_map(|s| sources.get(*s).map(|src| (*s, src.clone()))).collect();
+	if stages.is_empty() {
+		return result;
+	}
+	let mods = [
+		("base".to_string(), info.factorio_version_or_default()),
+		(info.name.clone(), info.version.clone()),
+	];
+	let lua = match sandbox(&info.name, sources, &mods) {
+		Ok(lua) => lua,
+		Err(e) => {
+			result.warnings.push(format!("Erro ao preparar o Lua: {}", e));
+			return result;
+		}
+	};
+	for (stage, source) in stages {
+		// Estágio com erro não impede os seguintes, como acontece com mods ausentes
+		if let Err(e) = lua.load(source.as_str()).set_name(format!("@{}", stage)).set_mode(ChunkMode::Text).exec() {
+			result.warnings.push(format!("{}: {}", stage, e));
+		}
+	}
+
+	let raw = lua.globals().raw_get::<_, Table>("data").and_then(|data| data.raw_get::<_, Table>("raw"));
+	let raw = match raw {
+		Ok(raw) => raw,
+		Err(e) => {
+			result.warnings.push(format!("data.raw inacessível: {}", e));
+			return result;
+		}
+	};
+	for kind in SETTING_TYPES {
+		let Ok(prototypes) = raw.raw_get::<_, Table>(kind) else { continue; };
+		for (_, prototype) in prototypes.pairs::<LuaValue, LuaValue>().flatten() {
+			match serde_json::from_value::<SettingDefinition>(lua_to_json(&prototype, 0)) {
+				Ok(definition) => result.settings.push(definition),
+				Err(e) => result.warnings.push(format!("Protótipo {} inválido: {}", kind, e)),
+			}
+		}
+	}
+	result.settings.sort_by(|a, b| {
+		a.setting_type.cmp(&b.setting_type).then(a.order.cmp(&b.order)).then(a.name.cmp(&b.name))
+	});
+	result
+}
+
+// Uma entrada por <mod>_<versão>: zips publicados não mudam sem trocar de versão
+fn cache_path(mod_name: &str, version: &str) -> PathBuf {
+	dirs::data_dir()
+		.unwrap_or(std::env::temp_dir())
+		.join("ModManager")
+		.join("setting-definitions")
+		.join(format!("{}_{}.json", mod_name, version))
+}
+
+// Definições de um mod instalado; pastas e symlinks são editados à mão e nunca entram no cache
+pub fn definitions_for(path: &Path, kind: mod_install::InstallKind) -> Result<ModSettingDefinitions, String> {
+	let info = mod_archive::read_info_json(path)?;
+	let cache = cache_path(&info.name, &info.version);
+	if kind.is_managed() {
+		if let Some(cached) = fs::read_to_string(&cache).ok().and_then(|c| serde_json::from_str(&c).ok()) {
+			return Ok(cached);
+		}
+	}
+	// Mods sem settings*.lua não precisam ter todos os .lua lidos
+	let has_stages = !mod_archive::read_mod_files(path, |p| STAGES.contains(&p))?.is_empty();
+	let sources = if has_stages {
+		mod_archive::read_mod_files(path, |p| p.ends_with(".lua"))?
+	} else {
+		BTreeMap::new()
+	};
+	let definitions = evaluate(&info, sources);
+	if kind.is_managed() {
+		if let Some(dir) = cache.parent() {
+			let _ = fs::create_dir_all(dir);
+		}
+		if let Ok(json) = serde_json::to_string(&definitions) {
+			let _ = fs::write(&cache, json);
+		}
+	}
+	Ok(definitions)
+}
+
+pub fn profile_definitions(profile_name: &str, mod_name: Option<&str>) -> Result<Vec<ModSettingDefinitions>, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(profile_name);
+	let mods_dir = crate::resolve_profile_mods_dir(&profile_dir)?;
+	let internal_list = crate::load_internal_mod_list(&profile_dir)?;
+	let mut results = Vec::new();
+	for entry in internal_list.mods.iter().filter(|m| mod_name.is_none_or(|n| n == m.name)) {
+		match definitions_for(&mods_dir.join(&entry.file_name), entry.kind) {
+			Ok(definitions) => results.push(definitions),
+			Err(e) => results.push(ModSettingDefinitions {
+				mod_name: entry.name.clone(),
+				version: entry.version.clone(),
+				warnings: vec![e],
+				..Default::default()
+			}),
+		}
+	}
+	Ok(results)
+}
+
+// Mods costumam prefixar as configurações com o próprio nome (ex.: "bobmods-plates-..."):
+// quanto mais longo o nome que casa, mais provável ser o dono
+fn owner_rank(mod_name: &str, setting_name: &str) -> Option<usize> {
+	let normalize = |s: &str| s.to_lowercase().replace('_', "-");
+	normalize(setting_name).starts_with(&normalize(mod_name)).then_some(mod_name.len())
+}
+
+// Definição de uma configuração entre os mods do perfil (None = nenhum mod instalado a declara).
+// Avalia primeiro os mods cujo nome prefixa a configuração e só cai nos demais se nenhum a declarar
+pub async fn find_definition(profile_name: &str, setting_name: &str) -> Result<Option<SettingDefinition>, String> {
+	let (profile_name, setting_name) = (profile_name.to_string(), setting_name.to_string());
+	tokio::task::spawn_blocking(move || {
+		let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+		let mods_dir = crate::resolve_profile_mods_dir(&profile_dir)?;
+		let internal_list = crate::load_internal_mod_list(&profile_dir)?;
+		let (mut likely, others): (Vec<_>, Vec<_>) = internal_list
+			.mods
+			.iter()
+			.partition(|m| owner_rank(&m.name, &setting_name).is_some());
+		likely.sort_by_key(|m| std::cmp::Reverse(owner_rank(&m.name, &setting_name)));
+		for entry in likely.into_iter().chain(others) {
+			// Mod que não avalia não declara nada aqui; os avisos aparecem na tela de definições
+			let Ok(definitions) = definitions_for(&mods_dir.join(&entry.file_name), entry.kind) else { continue };
+			if let Some(found) = definitions.settings.into_iter().find(|s| s.name == setting_name) {
+				return Ok(Some(found));
+			}
+		}
+		Ok(None)
+	})
+	.await
+	.map_err(|e| format!("Erro ao ler definições de configurações: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_mod_setting_definitions(
+	profile_name: String,
+	mod_name: Option<String>,
+) -> Result<Vec<ModSettingDefinitions>, String> {
+	tokio::task::spawn_blocking(move || profile_definitions(&profile_name, mod_name.as_deref()))
+		.await
+		.map_err(|e| format!("Erro ao ler definições de configurações: {}", e))?
+}
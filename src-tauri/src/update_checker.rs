@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::http_client::HttpService;
+use crate::version_pin;
+use crate::mod_version::cmp_versions;
+
+// Mod instalado com release mais nova no portal para a versão do Factorio do perfil
+#[derive(Debug, Clone, Serialize)]
+pub struct ModUpdate {
+	pub name: String,
+	pub installed_version: String,
+	pub latest_version: String,
+	pub factorio_version: String,
+	pub released_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProfileUpdates {
+	pub profile_name: String,
+	// Versão do Factorio do perfil; None = usa a de cada mod instalado
+	pub factorio_version: Option<String>,
+	pub updates: Vec<ModUpdate>,
+	// Releases mais novas que o pin ou a restrição do mod não deixam instalar
+	pub held: Vec<ModUpdate>,
+	// Mods que não puderam ser consultados (portal fora do ar, mod removido)
+	pub errors: Vec<String>,
+	pub checked_at: String,
+}
+
+// Consultas ao portal em paralelo por verificação; o resto espera na fila do stream
+const MAX_CONCURRENT_CHECKS: usize = 4;
+
+// Releases já consultadas, por mod; perfis com os mesmos mods e ciclos seguidos reaproveitam
+fn details_cache() -> &'static Mutex<HashMap<String, (Instant, crate::ModDetails)>> {
+	static CACHE: OnceLock<Mutex<HashMap<String, (Instant, crate::ModDetails)>>> = OnceLock::new();
+	CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+// Releases de cada mod: do cache se consultadas há menos de max_age (None = sempre consulta)
+async fn fetch_releases(
+	http: &HttpService,
+	names: Vec<String>,
+	max_age: Option<Duration>,
+) -> HashMap<String, Result<crate::ModDetails, String>> {
+	let mut found = HashMap::new();
+	let mut to_fetch = Vec::new();
+	{
+		let cache = details_cache().lock().unwrap();
+		for name in names {
+			match cache.get(&name).filter(|(at, _)| max_age.is_some_and(|age| at.elapsed() < age)) {
+				Some((_, details)) => { found.insert(name, Ok(details.clone())); }
+				None => to_fetch.push(name),
+			}
+		}
+	}
+	let fetched: Vec<(String, Result<crate::ModDetails, String>)> = stream::iter(to_fetch)
+		.map(|name| async move {
+			let details = crate::fetch_mod_details_internal(http, name.clone()).await;
+			(name, details)
+		})
+		.buffer_unordered(MAX_CONCURRENT_CHECKS)
+		.collect()
+		.await;
+	let mut cache = details_cache().lock().unwrap();
+	for (name, details) in fetched {
+		if let Ok(d) = &details {
+			cache.insert(name.clone(), (Instant::now(), d.clone()));
+		}
+		found.insert(name, details);
+	}
+	found
+}
+
+// Releases do portal declaram só major.minor ("2.0"); o perfil pode guardar "2.0.28"
+fn major_minor(version: &str) -> String {
+	version.split('.').take(2).collect::<Vec<_>>().join(".")
+}
+
+// max_age: consultas mais recentes que isso são reaproveitadas (verificação em segundo plano)
+pub async fn check_profile(http: &HttpService, profile_name: &str, max_age: Option<Duration>) -> Result<ProfileUpdates, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(profile_name);
+	let profile_fv = crate::load_profile_config(&profile_dir)?.factorio_version.filter(|v| !v.trim().is_empty());
+	let internal_list = crate::load_internal_mod_list(&profile_dir)?;
+
+	// Pastas e symlinks são mantidos à mão: não há o que atualizar pelo portal
+	let candidates: Vec<_> = internal_list.mods.into_iter().filter(|m| m.kind.is_managed()).collect();
+	let mut details = fetch_releases(http, candidates.iter().map(|m| m.name.clone()).collect(), max_age).await;
+
+	let mut result = ProfileUpdates {
+		profile_name: profile_name.to_string(),
+		factorio_version: profile_fv.clone(),
+		checked_at: chrono::Utc::now().to_rfc3339(),
+		..Default::default()
+	};
+	for installed in candidates.iter() {
+		let details = match details.remove(&installed.name).unwrap_or_else(|| Err("sem resposta".into())) {
+			Ok(d) => d,
+			Err(e) => {
+				result.errors.push(format!("{}: {}", installed.name, e));
+				continue;
+			}
+		};
+		let target_fv = major_minor(profile_fv.as_deref().unwrap_or(&installed.factorio_version));
+		let newer: Vec<_> = details.releases.iter()
+			.filter(|r| major_minor(&r.factorio_version) == target_fv)
+			.filter(|r| cmp_versions(&r.version, &installed.version) == std::cmp::Ordering::Greater)
+			.collect();
+		let allowed = |version: &str| {
+			!installed.pinned && installed.version_constraint.as_deref().map_or(true, |c| version_pin::satisfies(c, version))
+		};
+		let to_update = |release: &crate::ModVersionInfo| ModUpdate {
+			name: installed.name.clone(),
+			installed_version: installed.version.clone(),
+			latest_version: release.version.clone(),
+			factorio_version: release.factorio_version.clone(),
+			released_at: release.released_at.clone(),
+		};
+		let newest_allowed = newer.iter().copied().filter(|r| allowed(&r.version)).max_by(|a, b| cmp_versions(&a.version, &b.version));
+		let newest = newer.iter().copied().max_by(|a, b| cmp_versions(&a.version, &b.version));
+		if let Some(release) = newest_allowed {
+			result.updates.push(to_update(release));
+		}
+		// A release mais nova fica de fora por causa do pin/restrição
+		if let Some(release) = newest.filter(|r| !allowed(&r.version)) {
+			result.held.push(to_update(release));
+		}
+	}
+	Ok(result)
+}
+
+async fn check_all(http: &HttpService, max_age: Option<Duration>) -> Result<Vec<ProfileUpdates>, String> {
+	let mut results = Vec::new();
+	for profile in crate::get_profiles().await? {
+		match check_profile(http, &profile.folder_name, max_age).await {
+			Ok(updates) => results.push(updates),
+			Err(e) => results.push(ProfileUpdates {
+				profile_name: profile.folder_name.clone(),
+				errors: vec![e],
+				checked_at: chrono::Utc::now().to_rfc3339(),
+				..Default::default()
+			}),
+		}
+	}
+	Ok(results)
+}
+
+// Verifica atualizações de um perfil ou, sem profile_name, de todos
+#[tauri::command]
+pub async fn check_mod_updates(
+	http: tauri::State<'_, HttpService>,
+	profile_name: Option<String>,
+) -> Result<Vec<ProfileUpdates>, String> {
+	match profile_name {
+		Some(name) => Ok(vec![check_profile(&http, &name, None).await?]),
+		None => check_all(&http, None).await,
+	}
+}
+
+// Tarefa em segundo plano: relê AppConfig.update_check_interval_minutes a cada minuto,
+// então ligar/desligar ou mudar o intervalo vale sem reiniciar o app
+pub fn start_background_checks(app: AppHandle) {
+	tauri::async_runtime::spawn(async move {
+		let mut last_run: Option<Instant> = None;
+		loop {
+			let interval = crate::load_config().await.ok()
+				.and_then(|cfg| cfg.update_check_interval_minutes)
+				.filter(|m| *m > 0)
+				.map(|m| Duration::from_secs(m as u64 * 60));
+			if let Some(interval) = interval {
+				if last_run.map_or(true, |t| t.elapsed() >= interval) {
+					last_run = Some(Instant::now());
+					let http = app.state::<HttpService>().inner().clone();
+					// Mods já consultados dentro do intervalo (manualmente ou em outro perfil) não repetem a requisição
+					match check_all(&http, Some(interval)).await {
+						Ok(results) => {
+							if results.iter().any(|p| !p.updates.is_empty()) {
+								let _ = app.emit("updates-available", &results);
+							}
+						}
+						Err(e) => println!("Verificação de atualizações falhou: {}", e),
+					}
+				}
+			}
+			tokio::time::sleep(Duration::from_secs(60)).await;
+		}
+	});
+}
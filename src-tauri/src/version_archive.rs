@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{hooks, mod_archive, mod_install, mod_store, version_pin};
+use crate::mod_version::cmp_versions;
+
+// Versões substituídas de cada perfil ficam em <perfil>/archive (fora da pasta de mods,
+// que o Factorio carregaria), limitadas a AppConfig.archived_versions_per_mod por mod
+pub fn archive_dir(profile_dir: &Path) -> PathBuf {
+	profile_dir.join("archive")
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedVersion {
+	pub name: String,
+	pub version: String,
+	pub file_name: String,
+	pub size: u64,
+	// Data em que o zip saiu da pasta de mods
+	pub archived_at: Option<String>,
+}
+
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+	if to.exists() {
+		fs::remove_file(to)?;
+	}
+	// rename falha entre volumes (mods_path customizado em outro disco)
+	if fs::rename(from, to).is_err() {
+		fs::copy(from, to)?;
+		fs::remove_file(from)?;
+	}
+	Ok(())
+}
+
+// Versões arquivadas de um perfil (de um mod ou de todos), da mais nova para a mais antiga
+pub fn list_archived(profile_dir: &Path, mod_name: Option<&str>) -> Vec<ArchivedVersion> {
+	let Ok(entries) = fs::read_dir(archive_dir(profile_dir)) else { return Vec::new(); };
+	let mut versions: Vec<ArchivedVersion> = entries
+		.flatten()
+		.filter_map(|entry| {
+			let file_name = entry.file_name().to_string_lossy().to_string();
+			// Versão nunca tem "_", o nome do mod pode ter
+			let (name, version) = file_name.strip_suffix(".zip")?.rsplit_once('_')?;
+			if mod_name.is_some_and(|m| m != name) || !mod_install::is_versioned_zip_of(&file_name, name) {
+				return None;
+			}
+			let meta = entry.metadata().ok()?;
+			Some(ArchivedVersion {
+				name: name.to_string(),
+				version: version.to_string(),
+				file_name: file_name.clone(),
+				size: meta.len(),
+				archived_at: meta.modified().ok().map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+			})
+		})
+		.collect();
+	versions.sort_by(|a, b| a.name.cmp(&b.name).then(cmp_versions(&b.version, &a.version)));
+	versions
+}
+
+// Mantém só as `retention` versões arquivadas mais novas do mod
+fn prune(profile_dir: &Path, mod_name: &str, retention: u32) {
+	let dir = archive_dir(profile_dir);
+	for old in list_archived(profile_dir, Some(mod_name)).into_iter().skip(retention as usize) {
+		let _ = fs::remove_file(dir.join(&old.file_name));
+	}
+}
+
+// Tira da pasta de mods os outros <mod>_<versão>.zip, mantendo keep_file: vão para o arquivo
+// do perfil (retention = 0 apaga direto). Pastas e symlinks nunca são tocados
+pub fn archive_other_versions(profile_dir: &Path, mods_dir: &Path, mod_name: &str, keep_file: &str, retention: u32) {
+	let Ok(entries) = fs::read_dir(mods_dir) else { return; };
+	let superseded: Vec<PathBuf> = entries
+		.flatten()
+		.filter(|e| {
+			let name = e.file_name().to_string_lossy().to_string();
+			name != keep_file && mod_install::is_versioned_zip_of(&name, mod_name)
+		})
+		.map(|e| e.path())
+		.filter(|p| mod_install::install_kind(p).is_managed())
+		.collect();
+	if superseded.is_empty() {
+		return;
+	}
+	let dir = archive_dir(profile_dir);
+	for path in superseded {
+		if retention == 0 || fs::create_dir_all(&dir).is_err() {
+			let _ = mod_install::remove_installed(&path);
+			continue;
+		}
+		// O arquivo arquivado continua sendo um hardlink válido mesmo que o objeto saia do store
+		mod_store::release(&path);
+		let dest = dir.join(path.file_name().unwrap_or_default());
+		if let Err(e) = move_file(&path, &dest) {
+			println!("Aviso: não foi possível arquivar {}: {}", path.display(), e);
+			let _ = fs::remove_file(&path);
+		}
+	}
+	prune(profile_dir, mod_name, retention);
+}
+
+pub async fn archive_retention() -> u32 {
+	crate::load_config().await.map(|c| c.archived_versions_per_mod).unwrap_or(3)
+}
+
+#[tauri::command]
+pub async fn get_archived_versions(profile_name: String, mod_name: Option<String>) -> Result<Vec<ArchivedVersion>, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	Ok(list_archived(&profile_dir, mod_name.as_deref()))
+}
+
+// Volta uma versão arquivada para a pasta de mods; a versão atual vai para o arquivo no lugar dela
+#[tauri::command]
+pub async fn rollback_mod(app: AppHandle, profile: String, mod_name: String, version: String) -> Result<String, String> {
+	let profile_dir = crate::get_profiles_dir()?.join(&profile);
+	let mods_dir = crate::resolve_profile_mods_dir(&profile_dir)?;
+	let file_name = format!("{}_{}.zip", mod_name, version);
+	let archived = archive_dir(&profile_dir).join(&file_name);
+	if !archived.is_file() {
+		return Err(format!("{} v{} não está no arquivo do perfil", mod_name, version));
+	}
+	if let Some(existing) = mod_install::find_unmanaged(&mods_dir, &mod_name) {
+		return Err(format!("{} está instalado como pasta ou link ({}); remova-o antes do rollback", mod_name, existing.display()));
+	}
+	version_pin::check(&profile_dir, &mod_name, &version).map_err(|c| c.message)?;
+	let info = mod_archive::validate_mod_zip_async(&archived, &mod_name, &version).await
+		.map_err(|e| format!("Versão arquivada inválida: {}", e))?;
+
+	// Restaurar antes de arquivar a atual: a poda poderia apagar justamente a versão pedida
+	let dest = mods_dir.join(&file_name);
+	mod_store::detach(&dest);
+	move_file(&archived, &dest).map_err(|e| format!("Erro ao restaurar {}: {}", file_name, e))?;
+	archive_other_versions(&profile_dir, &mods_dir, &mod_name, &file_name, archive_retention().await.max(1));
+	if let Err(e) = mod_store::adopt(&dest, &mod_name, &version, None) {
+		println!("Aviso: {} v{} não entrou no store: {}", mod_name, version, e);
+	}
+
+	let previous_version = crate::add_mod_to_lists(&profile_dir, &file_name, &info)
+		.map_err(|e| format!("Erro ao atualizar listas de mods: {}", e))?;
+	hooks::fire_installed(&app, previous_version, hooks::HookContext {
+		mod_name: Some(mod_name.clone()),
+		version: Some(version.clone()),
+		profile_name: Some(profile.clone()),
+		file_path: Some(dest.to_string_lossy().to_string()),
+		mods_dir: Some(mods_dir.to_string_lossy().to_string()),
+		..Default::default()
+	});
+	Ok(format!("{} voltou para a versão {}", mod_name, version))
+}
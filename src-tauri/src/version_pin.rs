@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use chrono::Utc;
+use serde::Serialize;
+use crate::mod_version::cmp_versions;
+
+// Restrição de versão de um mod instalado (InternalModEntry.pinned / version_constraint).
+// Pinned = fica exatamente na versão instalada; a restrição (ex.: ">= 1.2, < 1.3") limita atualizações
+#[derive(Debug, Clone)]
+pub struct Hold {
+	pub installed_version: String,
+	pub pinned: bool,
+	pub constraint: Option<String>,
+}
+
+// Operação que mudaria um mod preso; vira erro em vez de acontecer em silêncio
+#[derive(Debug, Clone, Serialize)]
+pub struct PinConflict {
+	pub mod_name: String,
+	pub installed_version: String,
+	pub requested_version: String,
+	pub pinned: bool,
+	pub constraint: Option<String>,
+	pub message: String,
+}
+
+// Cláusulas separadas por vírgula: "<op> <versão>", op em <, <=, >, >=, = (sem op = igual)
+fn parse_constraint(constraint: &str) -> Result<Vec<(String, String)>, String> {
+	let mut clauses = Vec::new();
+	for raw in constraint.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+		let op_len = raw.find(|c: char| c.is_ascii_digit()).unwrap_or(raw.len());
+		let (op, version) = (raw[..op_len].trim(), raw[op_len..].trim());
+		if !matches!(op, "" | "=" | "==" | "<" | "<=" | ">" | ">=") {
+			return Err(format!("Operador inválido na restrição '{}': {}", constraint, op));
+		}
+		if version.is_empty() || !version.chars().all(|c| c.is_ascii_digit() || c == '.') {
+			return Err(format!("Versão inválida na restrição '{}': {}", constraint, raw));
+		}
+		clauses.push((op.to_string(), version.to_string()));
+	}
+	if clauses.is_empty() {
+		return Err("Restrição de versão vazia".into());
+	}
+	Ok(clauses)
+}
+
+// Restrição ilegível (lista editada à mão) não libera nada: nenhuma versão a atende até ser corrigida
+pub fn satisfies(constraint: &str, version: &str) -> bool {
+	use std::cmp::Ordering::*;
+	let Ok(clauses) = parse_constraint(constraint) else { return false; };
+	clauses.iter().all(|(op, bound)| {
+		let ord = cmp_versions(version, bound);
+		match op.as_str() {
+			"<" => ord == Less,
+			"<=" => ord != Greater,
+			">" => ord == Greater,
+			">=" => ord != Less,
+			_ => ord == Equal,
+		}
+	})
+}
+
+impl Hold {
+	pub fn allows(&self, version: &str) -> bool {
+		if self.pinned {
+			return version == self.installed_version;
+		}
+		self.constraint.as_deref().map_or(true, |c| satisfies(c, version))
+	}
+
+	pub fn conflict(&self, mod_name: &str, requested_version: &str) -> PinConflict {
+		let invalid = self.constraint.as_deref().and_then(|c| parse_constraint(c).err());
+		let message = if self.pinned {
+			format!("{} está fixado na versão {}; {} não será instalada", mod_name, self.installed_version, requested_version)
+		} else if let Some(error) = invalid {
+			format!("{}: corrija ou remova a restrição de {} antes de instalar {}", error, mod_name, requested_version)
+		} else {
+			format!(
+				"{} v{} não atende à restrição '{}' do perfil",
+				mod_name,
+				requested_version,
+				self.constraint.as_deref().unwrap_or_default()
+			)
+		};
+		PinConflict {
+			mod_name: mod_name.to_string(),
+			installed_version: self.installed_version.clone(),
+			requested_version: requested_version.to_string(),
+			pinned: self.pinned,
+			constraint: self.constraint.clone(),
+			message,
+		}
+	}
+}
+
+// Mods do perfil com pin ou restrição; lista ilegível = nenhuma restrição
+pub fn load_holds(profile_dir: &PathBuf) -> HashMap<String, Hold> {
+	let Ok(list) = crate::load_internal_mod_list(profile_dir) else { return HashMap::new(); };
+	list.mods
+		.into_iter()
+		.filter(|m| m.pinned || m.version_constraint.is_some())
+		.map(|m| (m.name, Hold { installed_version: m.version, pinned: m.pinned, constraint: m.version_constraint }))
+		.collect()
+}
+
+// Conflito se instalar <mod> v<version> no perfil mudaria um mod preso
+pub fn check(profile_dir: &PathBuf, mod_name: &str, version: &str) -> Result<(), PinConflict> {
+	match load_holds(profile_dir).get(mod_name) {
+		Some(hold) if !hold.allows(version) => Err(hold.conflict(mod_name, version)),
+		_ => Ok(()),
+	}
+}
+
+// Fixa/solta um mod e define (ou remove, com None/vazio) a restrição de versão
+#[tauri::command]
+pub async fn set_mod_hold(
+	profile_name: String,
+	mod_name: String,
+	pinned: bool,
+	constraint: Option<String>,
+) -> Result<String, String> {
+	let constraint = constraint.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+	if let Some(c) = constraint.as_deref() {
+		parse_constraint(c)?;
+	}
+	let profile_dir = crate::get_profiles_dir()?.join(&profile_name);
+	let mut internal_list = crate::load_internal_mod_list(&profile_dir)?;
+	let entry = internal_list.mods.iter_mut().find(|m| m.name == mod_name)
+		.ok_or_else(|| format!("Mod {} não está instalado no perfil", mod_name))?;
+	if let Some(c) = constraint.as_deref() {
+		if !pinned && !satisfies(c, &entry.version) {
+			return Err(format!("A versão instalada ({}) não atende à restrição '{}'", entry.version, c));
+		}
+	}
+	entry.pinned = pinned;
+	entry.version_constraint = constraint;
+	internal_list.last_updated = Utc::now().to_rfc3339();
+	crate::save_internal_mod_list(&profile_dir, &internal_list)?;
+	Ok(if pinned {
+		format!("Mod {} fixado na versão atual", mod_name)
+	} else {
+		format!("Restrições de versão de {} atualizadas", mod_name)
+	})
+}
@@ -87,11 +87,9 @@ export default function AllModsView() {
           return;
         }
 
+        // URL completa montada pelo backend com o host de assets configurado
         if (mod.thumbnail) {
-          const fullUrl = mod.thumbnail.startsWith('http') 
-            ? mod.thumbnail 
-            : `https://assets-mod.factorio.com${mod.thumbnail}`;
-          setThumbnailUrl(fullUrl);
+          setThumbnailUrl(mod.thumbnail);
           return;
         }
 
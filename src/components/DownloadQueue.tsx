@@ -1,8 +1,37 @@
 import { Pause, Play, ArrowUp, Trash2, CheckCircle2, Download as DownloadIcon, ListChecks, XCircle, Ban } from 'lucide-react';
-import { useDownloadQueue } from '../hooks/useDownloadQueue';
+import { useDownloadQueue, DownloadItem, DownloadFailure, FailureKind } from '../hooks/useDownloadQueue';
 import { useProfiles } from '../context/ProfileContext';
 import '../styles/DownloadQueue.css';
 
+const FAILURE_LABELS: Record<FailureKind, string> = {
+	not_found: 'Não encontrado',
+	server_error: 'Erro no servidor',
+	rate_limited: 'Limite de requisições',
+	http_client: 'Requisição recusada',
+	network: 'Erro de rede',
+	disk: 'Erro de disco',
+	insufficient_space: 'Sem espaço em disco',
+	integrity: 'Arquivo inválido',
+	cancelled: 'Cancelado',
+	deferred: 'Fora da janela de download',
+	other: 'Erro',
+};
+
+const failureOf = (item: DownloadItem): DownloadFailure | null =>
+	typeof item.status === 'object' && 'Failed' in item.status ? item.status.Failed : null;
+
+// Texto do status; falhas mostram o tipo, a mensagem e, se houver, a próxima tentativa automática
+const statusText = (item: DownloadItem) => {
+	const failure = failureOf(item);
+	if (!failure) return typeof item.status === 'string' ? item.status : '';
+	const kind = FAILURE_LABELS[failure.kind] ?? FAILURE_LABELS.other;
+	const http = failure.http_status ? ` (HTTP ${failure.http_status})` : '';
+	const retry = item.next_retry_at
+		? ` · nova tentativa às ${new Date(item.next_retry_at).toLocaleTimeString()}${item.attempts ? ` (tentativa ${item.attempts + 1})` : ''}`
+		: '';
+	return `${kind}${http}: ${failure.message}${retry}`;
+};
+
 export default function DownloadQueueView() {
 	const { items, loading, pauseAll, resumeAll, pause, resume, remove, prioritize, clearAll, cancelAll } = useDownloadQueue();
 	const { activeProfile } = useProfiles();
@@ -28,10 +57,11 @@ export default function DownloadQueueView() {
 	const downloadingItems = filtered.filter(i => typeof i.status === 'string' && i.status === 'Downloading');
 	const queuedItems = filtered.filter(i => typeof i.status === 'string' && i.status === 'Queued');
 	const pausedItems = filtered.filter(i => typeof i.status === 'string' && i.status === 'Paused');
+	const failedItems = filtered.filter(i => failureOf(i) !== null);
 	
 	// Primeiro item em download ou o primeiro da fila
 	const currentDownload = downloadingItems[0] || queuedItems[0] || pausedItems[0];
-	const upcomingDownloads = [...downloadingItems.slice(1), ...queuedItems, ...pausedItems].filter(item => item.id !== currentDownload?.id);
+	const upcomingDownloads = [...downloadingItems.slice(1), ...queuedItems, ...pausedItems, ...failedItems].filter(item => item.id !== currentDownload?.id);
 
 	const total = filtered.length;
 	const completed = completedItems.length;
@@ -72,7 +102,7 @@ export default function DownloadQueueView() {
 								<div className="download-info">
 									<div className="download-title">{currentDownload.mod_name}</div>
 									<div className="download-version">v{currentDownload.version}</div>
-									<div className="download-status">{typeof currentDownload.status === 'string' ? currentDownload.status : 'Failed'}</div>
+									<div className="download-status">{statusText(currentDownload)}</div>
 								</div>
 								
 								<div className="download-progress">
@@ -118,7 +148,7 @@ export default function DownloadQueueView() {
 											<div className="item-title">{item.mod_name}</div>
 											<div className="item-version">v{item.version}</div>
 										</div>
-										<div className="item-status">{typeof item.status === 'string' ? item.status : 'Failed'}</div>
+										<div className="item-status" title={statusText(item)}>{statusText(item)}</div>
 										<div className="item-actions">
 											<button onClick={() => prioritize(item.id)} title="Priorizar"><ArrowUp size={16} /></button>
 											<button onClick={() => remove(item.id)} title="Remover"><Trash2 size={16} /></button>
@@ -100,13 +100,12 @@ export default function ModDetailsView({ payload, onBack, onOpenDependency }: Pr
   // Build display images with fallback to thumbnail/cover if API has no gallery images
   const displayImages: ModImage[] = useMemo(() => {
     if (hasImages) return images;
-    const normalizeUrl = (u: string) => (u?.startsWith('http') ? u : `https://assets-mod.factorio.com${u}`);
+    // Backend already returns absolute URLs (configured assets host)
     const thumb = data?.enhanced_thumbnail || data?.thumbnail;
     if (thumb) {
-      const normalizedThumb = normalizeUrl(thumb);
       // Try to derive full-size from ".thumb.png" pattern; if it fails, use the same
-      const full = normalizedThumb.replace('.thumb.', '.');
-      return [{ id: 'thumb', url: full, thumbnail: normalizedThumb }];
+      const full = thumb.replace('.thumb.', '.');
+      return [{ id: 'thumb', url: full, thumbnail: thumb }];
     }
     return [];
   }, [hasImages, images, data?.enhanced_thumbnail, data?.thumbnail]);
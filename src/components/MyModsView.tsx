@@ -12,6 +12,16 @@ interface InstalledMod {
   file_name: string;
   download_date: string;
   factorio_version: string;
+  // Metadados do info.json (ausentes em entradas antigas até serem completadas)
+  title?: string | null;
+  author?: string | null;
+  description?: string | null;
+  dependencies?: string[];
+  // Pastas e symlinks (mods em desenvolvimento) não são apagados pelas limpezas automáticas
+  kind?: 'zip' | 'folder' | 'symlink';
+  // mod-name/mod-description do locale do mod no idioma do usuário
+  localised_title?: string | null;
+  localised_description?: string | null;
 }
 
 export default function MyModsView() {
@@ -95,7 +105,9 @@ export default function MyModsView() {
 
   // Filtrar mods baseado no termo de pesquisa
   const filteredMods = installedMods.filter(mod =>
-    mod.name.toLowerCase().includes(searchTerm.toLowerCase())
+    mod.name.toLowerCase().includes(searchTerm.toLowerCase()) ||
+    (mod.title ?? '').toLowerCase().includes(searchTerm.toLowerCase()) ||
+    (mod.localised_title ?? '').toLowerCase().includes(searchTerm.toLowerCase())
   );
 
   const handleToggleModStatus = async (modName: string, currentEnabled: boolean) => {
@@ -297,7 +309,7 @@ export default function MyModsView() {
               const evt = new CustomEvent('open-mod-details', { detail: { modName: mod.name, fromTab: 'my-mods' } });
               window.dispatchEvent(evt);
             }}>
-              <div className="mod-name">{mod.name} <span className="queue-version">v{mod.version}</span></div>
+              <div className="mod-name" title={mod.localised_description || mod.description || undefined}>{mod.localised_title || mod.title || mod.name} <span className="queue-version">v{mod.version}</span>{mod.kind && mod.kind !== 'zip' && <span className="queue-version"> {mod.kind === 'symlink' ? 'link' : 'pasta'}</span>}</div>
 
               <button
                 className={`toggle-switch ${mod.enabled ? 'active' : 'inactive'}`}
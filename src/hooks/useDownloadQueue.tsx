@@ -1,8 +1,16 @@
-import { useEffect, useState, useCallback } from 'react';
+import { useEffect, useState, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 
-export type DownloadStatus = 'Queued' | 'Downloading' | 'Paused' | 'Completed' | 'Removed' | { Failed: string };
+export type FailureKind = 'not_found' | 'server_error' | 'rate_limited' | 'http_client' | 'network' | 'disk' | 'insufficient_space' | 'integrity' | 'cancelled' | 'deferred' | 'other';
+
+export interface DownloadFailure {
+	kind: FailureKind;
+	message: string;
+	http_status?: number | null;
+}
+
+export type DownloadStatus = 'Queued' | 'Downloading' | 'Paused' | 'Completed' | 'Removed' | { Failed: DownloadFailure };
 
 export interface DownloadItem {
 	id: string;
@@ -14,16 +22,110 @@ export interface DownloadItem {
 	added_at: string;
 	speed_bps: number;
 	eta_secs?: number | null;
+	rate_limit_bps?: number | null;
+	attempts?: number;
+	next_retry_at?: string | null;
+	finished_at?: string | null;
+	batch_id?: string | null;
+	staged?: boolean;
+	expected_bytes?: number | null;
+	priority?: number;
+}
+
+export interface QueueFilter {
+	profile_name?: string | null;
+	batch_id?: string | null;
+}
+
+export interface QueueEstimate {
+	total_bytes: number;
+	remaining_bytes: number;
+	unknown_items: number;
+	eta_secs?: number | null;
+}
+
+export type QueueEvent = { seq: number } & (
+	| { type: 'added'; index: number; item: DownloadItem }
+	| { type: 'progress'; id: string; progress: number; speed_bps: number; eta_secs?: number | null }
+	| { type: 'status_changed'; item: DownloadItem }
+	| { type: 'removed'; id: string }
+	| { type: 'reordered'; ids: string[] }
+);
+
+interface QueueSnapshot {
+	seq: number;
+	items: DownloadItem[];
+}
+
+function applyQueueEvent(items: DownloadItem[], ev: QueueEvent): DownloadItem[] {
+	switch (ev.type) {
+		case 'added': {
+			const next = items.filter((i) => i.id !== ev.item.id);
+			next.splice(Math.min(ev.index, next.length), 0, ev.item);
+			return next;
+		}
+		case 'progress':
+			return items.map((i) => (i.id === ev.id ? { ...i, progress: ev.progress, speed_bps: ev.speed_bps, eta_secs: ev.eta_secs } : i));
+		case 'status_changed':
+			return items.map((i) => (i.id === ev.item.id ? ev.item : i));
+		case 'removed':
+			return items.filter((i) => i.id !== ev.id);
+		case 'reordered': {
+			const byId = new Map(items.map((i) => [i.id, i] as const));
+			return ev.ids.map((id) => byId.get(id)).filter((i): i is DownloadItem => !!i);
+		}
+	}
 }
 
+// Eventos guardados enquanto o snapshot carrega; os mais antigos saem (o snapshot já os cobre ou
+// a lacuna dispara outra ressincronização)
+const MAX_PENDING_EVENTS = 500;
+const SNAPSHOT_RETRY_MS = 2000;
+
 export function useDownloadQueue() {
 	const [items, setItems] = useState<DownloadItem[]>([]);
 	const [loading, setLoading] = useState(true);
+	// Último seq aplicado; null enquanto um snapshot está sendo carregado
+	const seqRef = useRef<number | null>(null);
+	const pendingRef = useRef<QueueEvent[]>([]);
+	const retryRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+
+	const pushPending = useCallback((ev: QueueEvent) => {
+		pendingRef.current.push(ev);
+		if (pendingRef.current.length > MAX_PENDING_EVENTS) {
+			pendingRef.current.splice(0, pendingRef.current.length - MAX_PENDING_EVENTS);
+		}
+	}, []);
 
 	const refresh = useCallback(async () => {
+		const previousSeq = seqRef.current;
+		seqRef.current = null;
+		if (retryRef.current) {
+			clearTimeout(retryRef.current);
+			retryRef.current = null;
+		}
 		try {
-			const data = await invoke<DownloadItem[]>('get_download_queue');
-			setItems(data);
+			const snap = await invoke<QueueSnapshot>('get_download_queue_snapshot');
+			// Eventos recebidos durante o carregamento que ainda não estão no snapshot
+			const newer = pendingRef.current.filter((ev) => ev.seq > snap.seq).sort((a, b) => a.seq - b.seq);
+			pendingRef.current = [];
+			let next = snap.items;
+			let seq = snap.seq;
+			for (const ev of newer) {
+				if (ev.seq !== seq + 1) break;
+				next = applyQueueEvent(next, ev);
+				seq = ev.seq;
+			}
+			seqRef.current = seq;
+			setItems(next);
+		} catch (e) {
+			console.error('Falha ao carregar a fila de downloads:', e);
+			// Volta a aplicar eventos sobre o estado atual e tenta o snapshot de novo
+			seqRef.current = previousSeq;
+			retryRef.current = setTimeout(() => {
+				retryRef.current = null;
+				refresh();
+			}, SNAPSHOT_RETRY_MS);
 		} finally {
 			setLoading(false);
 		}
@@ -31,13 +133,27 @@ export function useDownloadQueue() {
 
 	useEffect(() => {
 		refresh();
-		const unlistenPromise = listen<DownloadItem[]>('download-queue:update', (e) => {
-			setItems(e.payload);
+		const unlistenPromise = listen<QueueEvent>('download-queue:event', (e) => {
+			const ev = e.payload;
+			if (seqRef.current === null) {
+				pushPending(ev);
+				return;
+			}
+			if (ev.seq <= seqRef.current) return;
+			if (ev.seq !== seqRef.current + 1) {
+				// Evento perdido: ressincronizar pelo snapshot
+				pushPending(ev);
+				refresh();
+				return;
+			}
+			seqRef.current = ev.seq;
+			setItems((prev) => applyQueueEvent(prev, ev));
 		});
 		return () => {
 			unlistenPromise.then((u) => u());
+			if (retryRef.current) clearTimeout(retryRef.current);
 		};
-	}, [refresh]);
+	}, [refresh, pushPending]);
 
 	const enqueue = useCallback(async (mod_name: string, version: string, profile_name: string) => {
 		return invoke<string>('enqueue_download', { modName: mod_name, version, profileName: profile_name });
@@ -51,6 +167,14 @@ export function useDownloadQueue() {
 	const prioritize = useCallback((id: string) => invoke('move_to_top', { id }), []);
 	const clearAll = useCallback(() => invoke('clear_download_queue'), []);
 	const cancelAll = useCallback(() => invoke('cancel_all_downloads'), []);
+	const getEstimate = useCallback(() => invoke<QueueEstimate>('get_queue_estimate'), []);
+	const moveToPosition = useCallback((id: string, position: number) => invoke('move_to_position', { id, position }), []);
+	const moveUp = useCallback((id: string) => invoke('move_up', { id }), []);
+	const moveDown = useCallback((id: string) => invoke('move_down', { id }), []);
+	const setPriority = useCallback((id: string, priority: number) => invoke('set_download_priority', { id, priority }), []);
+	const pauseGroup = useCallback((filter: QueueFilter) => invoke('pause_group', { filter }), []);
+	const resumeGroup = useCallback((filter: QueueFilter) => invoke('resume_group', { filter }), []);
+	const cancelGroup = useCallback((filter: QueueFilter) => invoke('cancel_group', { filter }), []);
 
-	return { items, loading, refresh, enqueue, pauseAll, resumeAll, pause, resume, remove, prioritize, clearAll, cancelAll };
+	return { items, loading, refresh, enqueue, pauseAll, resumeAll, pause, resume, remove, prioritize, clearAll, cancelAll, getEstimate, moveToPosition, moveUp, moveDown, setPriority, pauseGroup, resumeGroup, cancelGroup };
 }
@@ -111,11 +111,9 @@ export function useModThumbnails() {
       return mod.enhanced_thumbnail;
     }
 
-    // 2. Fallback para thumbnail original (raramente disponível)
+    // 2. Fallback para thumbnail original (raramente disponível; o backend já devolve a URL completa)
     if (mod.thumbnail) {
-      return mod.thumbnail.startsWith('http') 
-        ? mod.thumbnail 
-        : `https://assets-mod.factorio.com${mod.thumbnail}`;
+      return mod.thumbnail;
     }
 
     // 3. Retornar null para usar placeholder SVG